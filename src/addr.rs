@@ -1,27 +1,23 @@
 //! Simple functions to add and delete IP addresses.
 
+#[cfg(feature = "route")]
+use crate::route::{Route4, Route6};
 use crate::{Connection, Error, Result};
 
-use std::net::IpAddr;
+#[cfg(feature = "route")]
+use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv6Addr};
 
 use futures::{future, TryStream, TryStreamExt};
-use netlink_packet_route::address::{AddressAttribute, AddressMessage, AddressScope};
+use netlink_packet_route::address::{
+    AddressAttribute, AddressFlag, AddressHeaderFlag, AddressMessage, AddressScope, CacheInfo,
+};
 use netlink_packet_route::AddressFamily;
 
 impl Connection {
     /// Flushes all addresses of an interface.
     pub async fn address_flush(&self, link: String) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
-
-        let id = link.header.index;
+        let id = self.resolve_index(&link).await?;
 
         let addrs: Vec<AddressMessage> = self
             .handle()
@@ -32,6 +28,10 @@ impl Connection {
             .try_collect()
             .await?;
 
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
         for addr in addrs {
             self.handle().address().del(addr).execute().await?;
         }
@@ -41,27 +41,18 @@ impl Connection {
 
     /// Flushes the IPv4 addresses of an interface.
     pub async fn address_flush4(&self, link: String) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+        let id = self.resolve_index(&link).await?;
 
-        let id = link.header.index;
+        // Let the kernel filter by address family
+        // instead of transferring all addresses.
+        let mut req = self.handle().address().get().set_link_index_filter(id);
+        req.message_mut().header.family = AddressFamily::Inet;
 
-        let addrs: Vec<AddressMessage> = self
-            .handle()
-            .address()
-            .get()
-            .set_link_index_filter(id)
-            .execute()
-            .try_filter(|addr| future::ready(addr.header.family == AddressFamily::Inet))
-            .try_collect()
-            .await?;
+        let addrs: Vec<AddressMessage> = req.execute().try_collect().await?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
 
         for addr in addrs {
             self.handle().address().del(addr).execute().await?;
@@ -72,27 +63,18 @@ impl Connection {
 
     /// Flushes the IPv6 addresses of an interface.
     pub async fn address_flush6(&self, link: String) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+        let id = self.resolve_index(&link).await?;
 
-        let id = link.header.index;
+        // Let the kernel filter by address family
+        // instead of transferring all addresses.
+        let mut req = self.handle().address().get().set_link_index_filter(id);
+        req.message_mut().header.family = AddressFamily::Inet6;
 
-        let addrs: Vec<AddressMessage> = self
-            .handle()
-            .address()
-            .get()
-            .set_link_index_filter(id)
-            .execute()
-            .try_filter(|addr| future::ready(addr.header.family == AddressFamily::Inet6))
-            .try_collect()
-            .await?;
+        let addrs: Vec<AddressMessage> = req.execute().try_collect().await?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
 
         for addr in addrs {
             self.handle().address().del(addr).execute().await?;
@@ -117,6 +99,10 @@ impl Connection {
             .try_collect()
             .await?;
 
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
         for addr in addrs {
             self.handle().address().del(addr).execute().await?;
         }
@@ -124,25 +110,120 @@ impl Connection {
         Ok(())
     }
 
-    /// Adds an IP address to an interface.
-    pub async fn address_add(&self, link: String, addr: IpAddr, prefix_len: u8) -> Result<()> {
-        let link = self
+    /// Flushes the addresses of a specific scope from an interface,
+    /// e.g. [`AddressScope::Universe`] to keep link-local addresses.
+    /// Returns the number of deleted addresses.
+    pub async fn address_flush_scope_link(
+        &self,
+        link: String,
+        scope: AddressScope,
+    ) -> Result<usize> {
+        let id = self.resolve_index(&link).await?;
+
+        let addrs: Vec<AddressMessage> = self
             .handle()
-            .link()
+            .address()
             .get()
-            .match_name(link.clone())
+            .set_link_index_filter(id)
             .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+            .try_filter(|addr| future::ready(addr.header.scope == scope))
+            .try_collect()
+            .await?;
 
-        let id = link.header.index;
+        let n = addrs.len();
 
-        self.handle()
-            .address()
-            .add(id, addr, prefix_len)
-            .execute()
-            .await?;
+        if self.is_dry_run() {
+            return Ok(n);
+        }
+
+        for addr in addrs {
+            self.handle().address().del(addr).execute().await?;
+        }
+
+        Ok(n)
+    }
+
+    /// Adds an IP address to an interface.
+    pub async fn address_add(&self, link: String, addr: IpAddr, prefix_len: u8) -> Result<()> {
+        self.address_add_full(link, addr, prefix_len, AddressScope::Universe, None, None)
+            .await
+    }
+
+    /// Adds an IP address to an interface unless it's already assigned.
+    ///
+    /// Returns `true` if the address was added and `false` if it already existed.
+    /// In dry-run mode the address is always reported as added.
+    pub async fn address_ensure(&self, link: String, addr: IpAddr, prefix_len: u8) -> Result<bool> {
+        match self.address_add(link, addr, prefix_len).await {
+            Ok(()) => Ok(true),
+            Err(Error::RtNetlink(e)) if is_eexist(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Adds multiple IP addresses to an interface,
+    /// resolving the interface only once.
+    ///
+    /// All addresses are attempted even if some of them fail.
+    /// The failed ones are reported together as [`Error::AddressesFailed`].
+    pub async fn address_add_many(&self, link: String, addrs: &[(IpAddr, u8)]) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        let mut failed = Vec::new();
+        for &(addr, prefix_len) in addrs {
+            if let Err(e) = self
+                .handle()
+                .address()
+                .add(id, addr, prefix_len)
+                .execute()
+                .await
+            {
+                failed.push((addr, prefix_len, e));
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::AddressesFailed(failed))
+        }
+    }
+
+    /// Adds an address to an interface with an explicit scope and lifetime.
+    ///
+    /// The lifetimes are in seconds, `None` means infinite.
+    /// If only `valid_lft` is specified the preferred lifetime defaults to it,
+    /// matching the behavior of `ip address add`.
+    /// The kernel rejects a preferred lifetime that exceeds the valid lifetime.
+    pub async fn address_add_full(
+        &self,
+        link: String,
+        addr: IpAddr,
+        prefix_len: u8,
+        scope: AddressScope,
+        valid_lft: Option<u32>,
+        preferred_lft: Option<u32>,
+    ) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().address().add(id, addr, prefix_len);
+        req.message_mut().header.scope = scope;
+
+        if let Some(cache_info) = cache_info(valid_lft, preferred_lft) {
+            req.message_mut()
+                .attributes
+                .push(AddressAttribute::CacheInfo(cache_info));
+        }
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        req.execute().await?;
 
         Ok(())
     }
@@ -155,40 +236,298 @@ impl Connection {
         addr: IpAddr,
         prefix_len: u8,
     ) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
-
-        let id = link.header.index;
+        let id = self.resolve_index(&link).await?;
 
         let mut req = self.handle().address().add(id, addr, prefix_len);
         req.message_mut().header.scope = AddressScope::Link;
 
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
         req.execute().await?;
 
         Ok(())
     }
 
+    /// Adds an IP address with additional address flags to an interface.
+    ///
+    /// Useful flags include:
+    ///
+    /// * [`AddressFlag::Noprefixroute`] - Don't install the on-link prefix route.
+    /// * [`AddressFlag::Nodad`] - Skip IPv6 duplicate address detection.
+    /// * [`AddressFlag::Managetempaddr`] - Let the kernel generate RFC 4941
+    ///   temporary addresses from this address. This only has an effect
+    ///   on IPv6 addresses with a prefix length of 64 and requires
+    ///   the `use_tempaddr` sysctl of the interface to be enabled.
+    pub async fn address_add_flags(
+        &self,
+        link: String,
+        addr: IpAddr,
+        prefix_len: u8,
+        flags: Vec<AddressFlag>,
+    ) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().address().add(id, addr, prefix_len);
+        req.message_mut()
+            .attributes
+            .push(AddressAttribute::Flags(flags));
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        req.execute().await?;
+
+        Ok(())
+    }
+
+    /// Adds an IP address to an interface without the automatic prefix route
+    /// and installs the connected route for its network explicitly.
+    ///
+    /// This is the same as [`Connection::address_add_flags`]
+    /// with [`AddressFlag::Noprefixroute`] followed by a gateway-less route
+    /// to the network of the address, which can then be managed
+    /// like any other route added by this crate.
+    /// IPv4 routes use the address as their preferred source address
+    /// like the prefix routes added by the kernel.
+    ///
+    /// If the route can't be added the address is removed again.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the interface to add the address to.
+    /// * `addr` - The IP address to add.
+    /// * `prefix_len` - The prefix length of the address and the route.
+    /// * `table` - The table to add the route to, e.g. 254 for the main table.
+    /// * `metric` - The metric (priority) of the route, if any.
+    #[cfg(feature = "route")]
+    pub async fn address_add_with_route(
+        &self,
+        link: String,
+        addr: IpAddr,
+        prefix_len: u8,
+        table: u32,
+        metric: Option<u32>,
+    ) -> Result<()> {
+        let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max_prefix_len {
+            return Err(Error::InvalidPrefixLength(prefix_len));
+        }
+
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().address().add(id, addr, prefix_len);
+        req.message_mut()
+            .attributes
+            .push(AddressAttribute::Flags(vec![AddressFlag::Noprefixroute]));
+        let message = req.message_mut().clone();
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        req.execute().await?;
+
+        let result = match addr {
+            IpAddr::V4(addr) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(prefix_len))
+                    .unwrap_or(0);
+
+                self.route_add_full4(Route4 {
+                    dst: Ipv4Addr::from(u32::from(addr) & mask),
+                    prefix_len,
+                    link: Some(link),
+                    table,
+                    metric,
+                    prefsrc: Some(addr),
+                    ..Default::default()
+                })
+                .await
+            }
+            IpAddr::V6(addr) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(prefix_len))
+                    .unwrap_or(0);
+
+                self.route_add_full6(Route6 {
+                    dst: Ipv6Addr::from(u128::from(addr) & mask),
+                    prefix_len,
+                    link: Some(link),
+                    table,
+                    metric,
+                    ..Default::default()
+                })
+                .await
+            }
+        };
+
+        if let Err(e) = result {
+            // The route error is more useful than a failure to clean up.
+            let _ = self.handle().address().del(message).execute().await;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Adds multiple IP addresses to an interface without inspecting
+    /// its current configuration first.
+    ///
+    /// Addresses that are already assigned are silently skipped.
+    /// Existing addresses that aren't in the list are left untouched,
+    /// so this is only equivalent to replacing the address configuration
+    /// if the interface is known to be unconfigured, e.g. right after creation.
+    /// Flush the interface first if that isn't guaranteed.
+    pub async fn address_set_initial(&self, link: String, addrs: Vec<(IpAddr, u8)>) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        for (addr, prefix_len) in addrs {
+            match self
+                .handle()
+                .address()
+                .add(id, addr, prefix_len)
+                .execute()
+                .await
+            {
+                Ok(_) => {}
+                Err(e) if is_eexist(&e) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reports whether an IP address is assigned to an interface
+    /// regardless of its prefix length or state.
+    pub async fn address_exists(&self, link: String, addr: IpAddr) -> Result<bool> {
+        self.address_exists_with(link, addr, None).await
+    }
+
+    /// Reports whether an IP address is assigned to an interface
+    /// with the specified prefix length.
+    pub async fn address_exists_cidr(
+        &self,
+        link: String,
+        addr: IpAddr,
+        prefix_len: u8,
+    ) -> Result<bool> {
+        self.address_exists_with(link, addr, Some(prefix_len)).await
+    }
+
+    async fn address_exists_with(
+        &self,
+        link: String,
+        addr: IpAddr,
+        prefix_len: Option<u8>,
+    ) -> Result<bool> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self
+            .handle()
+            .address()
+            .get()
+            .set_link_index_filter(id)
+            .set_address_filter(addr);
+
+        if let Some(prefix_len) = prefix_len {
+            req = req.set_prefix_length_filter(prefix_len);
+        }
+
+        Ok(req.execute().try_next().await?.is_some())
+    }
+
+    /// Reports whether an IP address is assigned to an interface and usable.
+    ///
+    /// An address isn't usable if it's still tentative (duplicate address
+    /// detection hasn't completed), if duplicate address detection failed
+    /// or if it's deprecated. This doesn't wait for the address to become ready.
+    pub async fn address_is_ready(&self, link: String, addr: IpAddr) -> Result<bool> {
+        let id = self.resolve_index(&link).await?;
+
+        let msg = self
+            .handle()
+            .address()
+            .get()
+            .set_link_index_filter(id)
+            .set_address_filter(addr)
+            .execute()
+            .try_next()
+            .await?;
+
+        let is_ready = match msg {
+            Some(msg) => {
+                let header_flags = [
+                    AddressHeaderFlag::Tentative,
+                    AddressHeaderFlag::Dadfailed,
+                    AddressHeaderFlag::Deprecated,
+                ];
+                let flags = [
+                    AddressFlag::Tentative,
+                    AddressFlag::Dadfailed,
+                    AddressFlag::Deprecated,
+                ];
+
+                !msg.header
+                    .flags
+                    .iter()
+                    .any(|flag| header_flags.contains(flag))
+                    && !msg.attributes.iter().any(|attr| {
+                        if let AddressAttribute::Flags(attr_flags) = attr {
+                            attr_flags.iter().any(|flag| flags.contains(flag))
+                        } else {
+                            false
+                        }
+                    })
+            }
+            None => false,
+        };
+
+        Ok(is_ready)
+    }
+
     /// Returns an iterator over the IP addresses of an interface.
     pub async fn address_get(
         &self,
         link: String,
     ) -> Result<impl TryStream<Ok = IpAddr, Error = Error>> {
-        let link = self
+        let link = self.link_message(&link).await?;
+
+        Ok(self
             .handle()
-            .link()
+            .address()
             .get()
-            .match_name(link.clone())
+            .set_link_index_filter(link.header.index)
             .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+            .err_into::<Error>()
+            .try_filter_map(|msg| {
+                future::ready(Ok(
+                    if let Some(AddressAttribute::Address(ip)) = msg.attributes.first() {
+                        match msg.header.family {
+                            AddressFamily::Inet | AddressFamily::Inet6 => Some(*ip),
+                            _ => None,
+                        }
+                    } else {
+                        None
+                    },
+                ))
+            }))
+    }
+
+    /// Returns an iterator over the IP addresses of an interface
+    /// and their prefix lengths.
+    pub async fn address_get_cidr(
+        &self,
+        link: String,
+    ) -> Result<impl TryStream<Ok = (IpAddr, u8), Error = Error>> {
+        let link = self.link_message(&link).await?;
 
         Ok(self
             .handle()
@@ -201,7 +540,9 @@ impl Connection {
                 future::ready(Ok(
                     if let Some(AddressAttribute::Address(ip)) = msg.attributes.first() {
                         match msg.header.family {
-                            AddressFamily::Inet | AddressFamily::Inet6 => Some(*ip),
+                            AddressFamily::Inet | AddressFamily::Inet6 => {
+                                Some((*ip, msg.header.prefix_len))
+                            }
                             _ => None,
                         }
                     } else {
@@ -210,4 +551,141 @@ impl Connection {
                 ))
             }))
     }
+
+    /// Returns the IPv6 addresses of an interface without the `IFA_F_PERMANENT` flag,
+    /// i.e. addresses with a finite lifetime.
+    ///
+    /// These are typically generated by SLAAC (including temporary addresses)
+    /// and expire unless they're refreshed by router advertisements.
+    /// Addresses added with a lifetime using [`Connection::address_add_full`]
+    /// are included too.
+    /// Link-local addresses are permanent even if the kernel generated them.
+    pub async fn address_get6_autoconf(&self, link: String) -> Result<Vec<Ipv6Addr>> {
+        self.address_get6_by_permanence(link, false).await
+    }
+
+    /// Returns the IPv6 addresses of an interface with the `IFA_F_PERMANENT` flag,
+    /// i.e. addresses without a finite lifetime.
+    ///
+    /// This includes link-local addresses generated by the kernel.
+    /// See [`Connection::address_get6_autoconf`] for the complement.
+    pub async fn address_get6_static(&self, link: String) -> Result<Vec<Ipv6Addr>> {
+        self.address_get6_by_permanence(link, true).await
+    }
+
+    async fn address_get6_by_permanence(
+        &self,
+        link: String,
+        permanent: bool,
+    ) -> Result<Vec<Ipv6Addr>> {
+        let id = self.resolve_index(&link).await?;
+
+        let addrs: Vec<AddressMessage> = self
+            .handle()
+            .address()
+            .get()
+            .set_link_index_filter(id)
+            .execute()
+            .try_filter(|addr| {
+                future::ready(
+                    addr.header.family == AddressFamily::Inet6 && is_permanent(addr) == permanent,
+                )
+            })
+            .try_collect()
+            .await?;
+
+        Ok(addrs
+            .into_iter()
+            .filter_map(|addr| {
+                addr.attributes.into_iter().find_map(|attr| {
+                    if let AddressAttribute::Address(IpAddr::V6(ip)) = attr {
+                        Some(ip)
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect())
+    }
+}
+
+fn is_eexist(e: &rtnetlink::Error) -> bool {
+    matches!(e, rtnetlink::Error::NetlinkError(msg) if msg.raw_code() == -libc::EEXIST)
+}
+
+fn is_permanent(addr: &AddressMessage) -> bool {
+    addr.header.flags.contains(&AddressHeaderFlag::Permanent)
+        || addr.attributes.iter().any(|attr| {
+            if let AddressAttribute::Flags(flags) = attr {
+                flags.contains(&AddressFlag::Permanent)
+            } else {
+                false
+            }
+        })
+}
+
+/// Builds the lifetime attribute of an address if any lifetime is specified.
+/// An unspecified valid lifetime is infinite,
+/// an unspecified preferred lifetime defaults to the valid lifetime.
+fn cache_info(valid_lft: Option<u32>, preferred_lft: Option<u32>) -> Option<CacheInfo> {
+    if valid_lft.is_none() && preferred_lft.is_none() {
+        return None;
+    }
+
+    let mut cache_info = CacheInfo::default();
+    cache_info.ifa_valid = valid_lft.unwrap_or(u32::MAX);
+    cache_info.ifa_preferred = preferred_lft.unwrap_or(cache_info.ifa_valid);
+
+    Some(cache_info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifetimes() {
+        assert_eq!(cache_info(None, None), None);
+
+        let lifetimes = cache_info(Some(3600), Some(1800)).unwrap();
+        assert_eq!(lifetimes.ifa_valid, 3600);
+        assert_eq!(lifetimes.ifa_preferred, 1800);
+
+        let lifetimes = cache_info(Some(600), None).unwrap();
+        assert_eq!(lifetimes.ifa_valid, 600);
+        assert_eq!(lifetimes.ifa_preferred, 600);
+
+        let lifetimes = cache_info(None, Some(300)).unwrap();
+        assert_eq!(lifetimes.ifa_valid, u32::MAX);
+        assert_eq!(lifetimes.ifa_preferred, 300);
+    }
+
+    #[cfg(feature = "route")]
+    #[tokio::test]
+    async fn with_route_prefix_len() {
+        let conn = Connection::new().await.unwrap();
+
+        assert!(matches!(
+            conn.address_add_with_route(
+                "lo".to_string(),
+                Ipv4Addr::new(192, 0, 2, 1).into(),
+                33,
+                254,
+                None
+            )
+            .await,
+            Err(Error::InvalidPrefixLength(33))
+        ));
+        assert!(matches!(
+            conn.address_add_with_route(
+                "lo".to_string(),
+                Ipv6Addr::LOCALHOST.into(),
+                129,
+                254,
+                None
+            )
+            .await,
+            Err(Error::InvalidPrefixLength(129))
+        ));
+    }
 }
@@ -1,31 +1,134 @@
 //! Blocking wrappers around the asynchronous API.
 //!
-//! All functions except for members of the tunnel module internally use their
-//! async counterparts inside a temporary tokio runtime.
-//! Tunnels are already synchronous.
+//! All functions except for members of the tunnel and ethtool modules
+//! internally use their async counterparts inside the tokio runtime of the connection,
+//! see [`Connection::new`] and [`Connection::with_runtime`].
+//! Tunnels and ethtool operations are already synchronous.
 //!
 //! Consult the async modules for documentation.
 
+#[cfg(feature = "ethtool")]
+pub use crate::ethtool;
 #[cfg(feature = "tunnel")]
 pub use crate::tunnel;
 
+use std::future::Future;
+
 /// A blocking wrapper around the async [`crate::Connection`].
 #[derive(Debug)]
 pub struct Connection {
-    rt: tokio::runtime::Runtime,
+    rt: Runtime,
     conn: crate::Connection,
 }
 
 impl Connection {
-    /// Creates a new blocking wrapper around [`crate::Connection`].
+    /// Creates a new blocking wrapper around [`crate::Connection`]
+    /// with its own single-threaded tokio runtime.
+    ///
+    /// The runtime runs on the calling thread and doesn't spawn any threads.
+    /// The connection task only makes progress while a method is blocking,
+    /// which is all it needs to do. Use [`Connection::with_runtime`]
+    /// to avoid creating a runtime per connection.
     pub fn new() -> crate::Result<Self> {
-        let rt = tokio::runtime::Runtime::new()?;
+        let rt = runtime()?;
 
         Ok(Self {
             conn: rt.block_on(crate::Connection::new())?,
-            rt,
+            rt: Runtime::Owned(rt),
+        })
+    }
+
+    /// Creates a new blocking wrapper around [`crate::Connection`]
+    /// that runs on an existing tokio runtime instead of creating its own.
+    /// This allows many blocking connections, e.g. one per worker thread,
+    /// to share a single runtime.
+    ///
+    /// This takes a [`tokio::runtime::Handle`] rather than a
+    /// [`tokio::runtime::Runtime`] because a runtime can only have one owner,
+    /// so taking it would prevent sharing it. The runtime must outlive
+    /// the connection.
+    ///
+    /// The runtime must be a multi-threaded runtime. The connection task
+    /// is spawned onto it and has to make progress on the runtime's worker threads
+    /// while the calling thread is blocked. As with [`Connection::new`],
+    /// the methods must not be called from within an async context.
+    pub fn with_runtime(rt: tokio::runtime::Handle) -> crate::Result<Self> {
+        Ok(Self {
+            conn: rt.block_on(crate::Connection::new())?,
+            rt: Runtime::Shared(rt),
+        })
+    }
+
+    /// Creates a new blocking wrapper around [`crate::Connection`]
+    /// that operates inside a named network namespace
+    /// with its own single-threaded tokio runtime like [`Connection::new`].
+    /// See [`crate::Connection::new_in_namespace`].
+    pub fn new_in_namespace(name: &str) -> crate::Result<Self> {
+        let rt = runtime()?;
+
+        Ok(Self {
+            conn: rt.block_on(crate::Connection::new_in_namespace(name))?,
+            rt: Runtime::Owned(rt),
         })
     }
+
+    /// Replaces the connection with a freshly established one.
+    /// See [`crate::Connection::reconnect`].
+    pub fn reconnect(&mut self) -> crate::Result<()> {
+        self.rt.block_on(self.conn.reconnect())
+    }
+
+    /// Enables or disables dry-run mode.
+    /// See [`crate::Connection::dry_run`].
+    pub fn dry_run(&mut self, enabled: bool) {
+        self.conn.dry_run(enabled)
+    }
+
+    /// Reports whether dry-run mode is enabled.
+    /// See [`crate::Connection::is_dry_run`].
+    pub fn is_dry_run(&self) -> bool {
+        self.conn.is_dry_run()
+    }
+
+    /// Enables or disables caching of interface indices.
+    /// See [`crate::Connection::name_cache`].
+    pub fn name_cache(&mut self, enabled: bool) {
+        self.conn.name_cache(enabled)
+    }
+
+    /// Clears the interface index cache.
+    /// See [`crate::Connection::invalidate_cache`].
+    pub fn invalidate_cache(&self) {
+        self.conn.invalidate_cache()
+    }
+
+    /// Returns the netlink port ID the kernel assigned to the socket.
+    /// See [`crate::Connection::port_id`].
+    pub fn port_id(&self) -> u32 {
+        self.conn.port_id()
+    }
+}
+
+/// Creates the single-threaded runtime of a connection.
+fn runtime() -> std::io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+}
+
+#[derive(Debug)]
+enum Runtime {
+    Owned(tokio::runtime::Runtime),
+    Shared(tokio::runtime::Handle),
+}
+
+impl Runtime {
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        match self {
+            Self::Owned(rt) => rt.block_on(future),
+            Self::Shared(handle) => handle.block_on(future),
+        }
+    }
 }
 
 macro_rules! blockify {
@@ -39,6 +142,11 @@ macro_rules! blockify {
             self.rt.block_on(self.conn.$blk($($v),*))
         }
     };
+    ($blk:ident -> $ret:ty) => {
+        pub fn $blk(&self) -> crate::Result<$ret> {
+            self.rt.block_on(self.conn.$blk())
+        }
+    };
     ($blk:ident -> $ret:ty, $($v:tt: $t:ty),*) => {
         pub fn $blk(&self, $($v: $t),*) -> crate::Result<$ret> {
             self.rt.block_on(self.conn.$blk($($v),*))
@@ -50,22 +158,42 @@ macro_rules! blockify {
 pub mod addr {
     use super::Connection;
 
-    use std::net::IpAddr;
+    use std::net::{IpAddr, Ipv6Addr};
 
     use futures::TryStreamExt;
+    use netlink_packet_route::address::{AddressFlag, AddressScope};
 
     impl Connection {
         blockify!(address_flush, link: String);
         blockify!(address_flush4, link: String);
         blockify!(address_flush6, link: String);
         blockify!(address_flush6_global);
+        blockify!(address_flush_scope_link -> usize, link: String, scope: AddressScope);
         blockify!(address_add, link: String, addr: IpAddr, prefix_len: u8);
+        blockify!(address_ensure -> bool, link: String, addr: IpAddr, prefix_len: u8);
+        blockify!(address_add_many, link: String, addrs: &[(IpAddr, u8)]);
+        blockify!(address_add_full, link: String, addr: IpAddr, prefix_len: u8, scope: AddressScope, valid_lft: Option<u32>, preferred_lft: Option<u32>);
         blockify!(address_add_link_local, link: String, addr: IpAddr, prefix_len: u8);
+        blockify!(address_add_flags, link: String, addr: IpAddr, prefix_len: u8, flags: Vec<AddressFlag>);
+        blockify!(address_set_initial, link: String, addrs: Vec<(IpAddr, u8)>);
+        #[cfg(feature = "route")]
+        blockify!(address_add_with_route, link: String, addr: IpAddr, prefix_len: u8, table: u32, metric: Option<u32>);
+
+        blockify!(address_exists -> bool, link: String, addr: IpAddr);
+        blockify!(address_exists_cidr -> bool, link: String, addr: IpAddr, prefix_len: u8);
+        blockify!(address_is_ready -> bool, link: String, addr: IpAddr);
+        blockify!(address_get6_autoconf -> Vec<Ipv6Addr>, link: String);
+        blockify!(address_get6_static -> Vec<Ipv6Addr>, link: String);
 
         pub fn address_get(&self, link: String) -> crate::Result<Vec<IpAddr>> {
             self.rt
                 .block_on(async { self.conn.address_get(link).await?.try_collect().await })
         }
+
+        pub fn address_get_cidr(&self, link: String) -> crate::Result<Vec<(IpAddr, u8)>> {
+            self.rt
+                .block_on(async { self.conn.address_get_cidr(link).await?.try_collect().await })
+        }
     }
 }
 
@@ -73,23 +201,97 @@ pub mod addr {
 pub mod link {
     use super::Connection;
 
+    #[cfg(feature = "link")]
+    use crate::link::BridgeOpts;
+    use crate::link::{LinkCapability, LinkInfo};
+
+    use std::collections::HashMap;
+    #[cfg(feature = "link")]
+    use std::net::Ipv4Addr;
+    use std::net::Ipv6Addr;
+    #[cfg(feature = "link")]
+    use std::os::fd::RawFd;
+    use std::time::Duration;
+
+    use netlink_packet_route::link::State;
+
     impl Connection {
         #[cfg(feature = "link")]
         blockify!(link_set, link: String, state: bool);
         #[cfg(feature = "link")]
         blockify!(link_set_mtu, link: String, mtu: u32);
         #[cfg(feature = "link")]
+        blockify!(link_set_txqueuelen, link: String, len: u32);
+        #[cfg(feature = "link")]
+        blockify!(link_set_promisc, link: String, on: bool);
+        #[cfg(feature = "link")]
+        blockify!(link_set_mac, link: String, mac: [u8; 6]);
+        #[cfg(feature = "link")]
+        blockify!(link_set_broadcast, link: String, bcast: [u8; 6]);
+        #[cfg(feature = "link")]
+        blockify!(link_set_gso_max, link: String, size: Option<u32>, segs: Option<u32>);
+        #[cfg(feature = "link")]
+        blockify!(link_set_ipv6_token, link: String, token: Ipv6Addr);
+        #[cfg(feature = "link")]
+        blockify!(link_set_dormant, link: String, dormant: bool);
+        #[cfg(feature = "link")]
+        blockify!(link_set_protodown, link: String, down: bool, reason: Option<u32>);
+        #[cfg(feature = "link")]
         blockify!(link_add_vlan, link: String, parent: String, vlan_id: u16);
         #[cfg(feature = "link")]
+        blockify!(link_del_vlan, parent: String, vlan_id: u16);
+        #[cfg(feature = "link")]
+        blockify!(link_vlan_add_egress_map, link: String, from_prio: u32, to_pcp: u32);
+        #[cfg(feature = "link")]
+        blockify!(link_set_bridge_opts, link: String, opts: BridgeOpts);
+        #[cfg(feature = "link")]
         blockify!(link_add_wireguard, link: String);
         #[cfg(feature = "link")]
+        blockify!(link_add_veth, link: String, peer: String);
+        #[cfg(feature = "link")]
+        blockify!(link_add_veth_with_index, link: String, peer: String, index: u32, peer_index: u32);
+        #[cfg(feature = "link")]
+        blockify!(link_add_bridge, link: String);
+        #[cfg(feature = "link")]
+        blockify!(link_add_vrf, link: String, table: u32);
+        #[cfg(feature = "link")]
+        blockify!(link_set_master, link: String, master: String);
+        #[cfg(feature = "link")]
+        blockify!(link_set_nomaster, link: String);
+        #[cfg(feature = "link")]
+        blockify!(link_set_netns_pid, link: String, pid: u32);
+        #[cfg(feature = "link")]
+        blockify!(link_set_netns_fd, link: String, fd: RawFd);
+        #[cfg(feature = "link")]
+        blockify!(link_add_gretap, link: String, local: Ipv4Addr, remote: Ipv4Addr, key: Option<u32>);
+        #[cfg(feature = "link")]
         blockify!(link_delete, link: String);
 
         blockify!(link_is_up -> bool, link: String);
+        blockify!(link_oper_state -> State, link: String);
+        blockify!(link_has_carrier -> bool, link: String);
+        blockify!(link_protodown -> bool, link: String);
         blockify!(link_wait_up, link: String);
+        blockify!(link_wait_up_timeout -> bool, link: String, interval: Duration, timeout: Duration);
+        blockify!(link_wait_up_timeout_or_err, link: String, interval: Duration, timeout: Duration);
         blockify!(link_exists -> bool, link: String);
+        blockify!(link_exists_exact -> bool, link: String);
         blockify!(link_wait_exists, link: String);
         blockify!(link_index -> u32, link: String);
+        blockify!(link_index_opt -> Option<u32>, link: String);
+        blockify!(link_master -> Option<String>, link: String);
+        blockify!(link_promisc_count -> u32, link: String);
+        blockify!(link_index_map -> HashMap<u32, String>);
+        blockify!(link_get_mtu -> u32, link: String);
+        blockify!(link_mtu_range -> (u32, u32), link: String);
+        blockify!(link_gso_max -> (u32, u32), link: String);
+        blockify!(link_netnsid -> Option<i32>, link: String);
+        blockify!(link_get_mac -> [u8; 6], link: String);
+        blockify!(link_info -> LinkInfo, link: String);
+        blockify!(link_carrier_changes -> u32, link: String);
+        blockify!(link_qdisc -> Option<String>, link: String);
+        blockify!(link_ipv6_token -> Option<Ipv6Addr>, link: String);
+        blockify!(link_supports -> bool, link: String, capability: LinkCapability);
     }
 }
 
@@ -97,13 +299,50 @@ pub mod link {
 pub mod route {
     use super::Connection;
 
+    use crate::route::{DefaultRoute, IpVersion, Route4, Route6, RouteFilter};
+
+    use std::collections::BTreeSet;
     use std::net::{Ipv4Addr, Ipv6Addr};
+    use std::time::Duration;
 
     impl Connection {
         blockify!(route_flush4, link: String);
         blockify!(route_flush6, link: String);
         blockify!(route_flush, link: String);
+        blockify!(route_flush_filtered -> usize, filter: RouteFilter);
+        blockify!(route_del_within4 -> usize, supernet: Ipv4Addr, prefix_len: u8, table: Option<u32>);
+        blockify!(route_del_within6 -> usize, supernet: Ipv6Addr, prefix_len: u8, table: Option<u32>);
         blockify!(route_add4, dst: Ipv4Addr, prefix_len: u8, rtr: Option<Ipv4Addr>, link: String);
         blockify!(route_add6, dst: Ipv6Addr, prefix_len: u8, rtr: Option<Ipv6Addr>, link: String);
+        blockify!(route_add_full4, route: Route4);
+        blockify!(route_add_full6, route: Route6);
+        blockify!(route_validate4, route: &Route4);
+        blockify!(route_validate6, route: &Route6);
+        blockify!(route_del4, route: Route4);
+        blockify!(route_del6, route: Route6);
+        blockify!(route_move_table4, route: Route4, table: u32);
+        blockify!(route_move_table6, route: Route6, table: u32);
+        blockify!(route_add_ra_default6, gateway: Ipv6Addr, link: String, lifetime: Duration);
+        blockify!(route_get4 -> Option<Route4>, dst: Ipv4Addr);
+        blockify!(route_get6 -> Option<Route6>, dst: Ipv6Addr);
+        blockify!(route_pmtu4 -> Option<u32>, dst: Ipv4Addr);
+        blockify!(route_pmtu6 -> Option<u32>, dst: Ipv6Addr);
+        blockify!(routes4 -> Vec<Route4>, table: Option<u32>);
+        blockify!(routes6 -> Vec<Route6>, table: Option<u32>);
+        blockify!(route_default_detailed4 -> Vec<DefaultRoute>);
+        blockify!(route_tables_in_use -> BTreeSet<u32>, version: IpVersion);
+        blockify!(route_find_table4 -> Vec<u32>, dst: Ipv4Addr, prefix_len: u8);
+        blockify!(route_find_table6 -> Vec<u32>, dst: Ipv6Addr, prefix_len: u8);
+        blockify!(route_wait4 -> bool, dst: Ipv4Addr, prefix_len: u8, table: Option<u32>, interval: Duration, timeout: Duration);
+        blockify!(route_wait6 -> bool, dst: Ipv6Addr, prefix_len: u8, table: Option<u32>, interval: Duration, timeout: Duration);
+    }
+}
+
+#[cfg(all(feature = "addr", feature = "route", feature = "link"))]
+pub mod recipes {
+    use super::Connection;
+
+    impl Connection {
+        blockify!(link_decommission, link: String);
     }
 }
@@ -1,23 +1,261 @@
-use crate::Result;
+#[cfg(any(feature = "addr", feature = "route", feature = "status"))]
+use crate::Error;
+use crate::{netns, Result};
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[cfg(any(feature = "addr", feature = "route", feature = "status"))]
+use futures::TryStreamExt;
+#[cfg(any(feature = "addr", feature = "route", feature = "status"))]
+use netlink_packet_route::link::LinkMessage;
+use netlink_sys::AsyncSocket;
 use rtnetlink::Handle;
+use tokio::task::JoinHandle;
 
 #[derive(Debug)]
-pub struct Connection(Handle);
+pub struct Connection {
+    handle: Handle,
+    port_id: u32,
+    task: JoinHandle<()>,
+    netns: Option<PathBuf>,
+    dry_run: bool,
+    name_cache: Option<Mutex<HashMap<String, u32>>>,
+}
 
 impl Connection {
     /// Creates a new connection and handle to rtnetlink and spawns the connection task.
     /// Can be used to interact with rtnetlink by enabling certain crate features
     /// and calling the methods they provide.
     pub async fn new() -> Result<Self> {
-        let (conn, handle, _) = rtnetlink::new_connection()?;
-        tokio::spawn(conn);
+        Self::connect(None).await
+    }
+
+    /// Creates a new connection like [`Connection::new`]
+    /// that operates inside the named network namespace at `/var/run/netns/<name>`,
+    /// e.g. one created by `ip netns add`. Requires `CAP_SYS_ADMIN`.
+    ///
+    /// The calling thread enters the namespace using `setns(2)` while the socket
+    /// is created and returns to its original namespace right afterwards.
+    /// The namespace is switched and restored on the calling thread
+    /// before anything yields, so no other task can run on the thread in the meantime.
+    /// Only code interrupting the thread (e.g. signal handlers)
+    /// observes the target namespace. Once created, all requests
+    /// as well as [`crate::monitor`] streams (if enabled) operate in the namespace
+    /// regardless of the thread they're made from.
+    pub async fn new_in_namespace(name: &str) -> Result<Self> {
+        Self::connect(Some(Path::new("/var/run/netns").join(name))).await
+    }
+
+    async fn connect(netns: Option<PathBuf>) -> Result<Self> {
+        let open = || {
+            let (mut conn, handle, _) = rtnetlink::new_connection()?;
+            let port_id = conn.socket_mut().socket_mut().bind_auto()?.port_number();
+
+            Ok((conn, handle, port_id))
+        };
+
+        let (conn, handle, port_id) = match netns {
+            Some(ref path) => netns::with_netns(path, open)?,
+            None => open()?,
+        };
+        let task = tokio::spawn(conn);
+
+        Ok(Self {
+            handle,
+            port_id,
+            task,
+            netns,
+            dry_run: false,
+            name_cache: None,
+        })
+    }
 
-        Ok(Self(handle))
+    /// Replaces the connection with a freshly established one,
+    /// e.g. after the connection task died because of a socket error.
+    ///
+    /// The old connection task is aborted. Requests that are still in flight
+    /// on the old connection fail and need to be retried after reconnecting.
+    /// The new connection is assigned a new [`Connection::port_id`]
+    /// and operates in the same network namespace.
+    pub async fn reconnect(&mut self) -> Result<()> {
+        let mut new = Self::connect(self.netns.clone()).await?;
+        new.dry_run = self.dry_run;
+        if self.name_cache.is_some() {
+            new.name_cache = Some(Mutex::default());
+        }
+        self.task.abort();
+
+        *self = new;
+        Ok(())
+    }
+
+    /// Enables or disables dry-run mode. Disabled by default.
+    ///
+    /// In dry-run mode the following operations still resolve interfaces
+    /// and dump the objects they'd modify, but return successfully
+    /// without sending any modifying requests to the kernel:
+    ///
+    /// * The `address_add*` functions, `address_ensure` and `address_set_initial`.
+    /// * The `address_flush*` functions.
+    /// * `route_add4`, `route_add6` and `route_add_ra_default6`.
+    /// * `route_add_full4`, `route_add_full6`, `route_del4`, `route_del6`
+    ///   and the `route_move_table*` functions.
+    /// * The `route_flush*` and `route_del_within*` functions.
+    /// * `link_decommission`.
+    ///
+    /// Functions returning the number of affected objects
+    /// report the number of objects they would have deleted.
+    /// All other operations, including link configuration, ignore the flag.
+    pub fn dry_run(&mut self, enabled: bool) {
+        self.dry_run = enabled;
+    }
+
+    /// Reports whether dry-run mode is enabled. See [`Connection::dry_run`].
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Enables or disables caching of interface indices. Disabled by default.
+    ///
+    /// Most functions resolve interface names to indices using a separate request.
+    /// With the cache enabled, each name is only resolved once
+    /// which saves a request per call when configuring the same interfaces repeatedly.
+    ///
+    /// The cache is invalidated for interfaces deleted or moved to another
+    /// network namespace using this connection. Changes made by other processes
+    /// or connections (e.g. an interface being deleted and recreated
+    /// with the same name) aren't noticed. Operations then fail
+    /// or affect whichever interface now has the cached index.
+    /// Use [`Connection::invalidate_cache`] after such changes.
+    /// Disabling the cache clears it. Reconnecting keeps it enabled but clears it.
+    pub fn name_cache(&mut self, enabled: bool) {
+        self.name_cache = if enabled {
+            Some(Mutex::default())
+        } else {
+            None
+        };
+    }
+
+    /// Clears the interface index cache, see [`Connection::name_cache`].
+    pub fn invalidate_cache(&self) {
+        if let Some(ref cache) = self.name_cache {
+            cache.lock().unwrap().clear();
+        }
+    }
+
+    /// Returns the netlink port ID the kernel assigned to the socket.
+    ///
+    /// This is the address of the socket within the netlink protocol family
+    /// and the `nlmsg_pid` of requests sent by this connection,
+    /// not the process ID of the caller.
+    /// It can be used to attribute changes seen by `ip monitor`
+    /// to this connection when multiple processes configure interfaces.
+    pub fn port_id(&self) -> u32 {
+        self.port_id
+    }
+
+    /// Returns a reference to the underlying [`rtnetlink::Handle`]
+    /// for sending requests this crate doesn't provide a wrapper for.
+    ///
+    /// The handle is an `rtnetlink` type. Its API may change
+    /// whenever this crate updates its `rtnetlink` dependency,
+    /// even if the rest of this crate's API stays the same.
+    pub fn handle_raw(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// Returns the path of the network namespace the connection operates in,
+    /// or `None` for the namespace of the process.
+    #[cfg(feature = "monitor")]
+    pub(crate) fn netns(&self) -> Option<&Path> {
+        self.netns.as_deref()
     }
 
     /// Returns a reference to the underlying [`rtnetlink::Handle`].
+    #[cfg(any(feature = "addr", feature = "route", feature = "status"))]
     pub(crate) fn handle(&self) -> &Handle {
-        &self.0
+        &self.handle
+    }
+
+    /// Resolves an interface name to its index,
+    /// using the cache if enabled (see [`Connection::name_cache`]).
+    /// Fails with [`Error::LinkNotFound`] if the interface doesn't exist.
+    #[cfg(any(feature = "addr", feature = "route", feature = "status"))]
+    pub(crate) async fn resolve_index(&self, name: &str) -> Result<u32> {
+        if let Some(ref cache) = self.name_cache {
+            if let Some(&index) = cache.lock().unwrap().get(name) {
+                return Ok(index);
+            }
+        }
+
+        let index = self.link_message(name).await?.header.index;
+
+        if let Some(ref cache) = self.name_cache {
+            cache.lock().unwrap().insert(name.to_string(), index);
+        }
+
+        Ok(index)
+    }
+
+    /// Fetches an interface by name.
+    /// Fails with [`Error::LinkNotFound`] if the interface doesn't exist.
+    #[cfg(any(feature = "addr", feature = "route", feature = "status"))]
+    pub(crate) async fn link_message(&self, name: &str) -> Result<LinkMessage> {
+        match self
+            .handle()
+            .link()
+            .get()
+            .match_name(name.to_string())
+            .execute()
+            .try_next()
+            .await
+        {
+            Ok(Some(link)) => Ok(link),
+            Ok(None) => Err(Error::LinkNotFound(name.to_string())),
+            Err(rtnetlink::Error::NetlinkError(e)) if e.raw_code() == -libc::ENODEV => {
+                Err(Error::LinkNotFound(name.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Removes an interface from the index cache, if enabled.
+    #[cfg(feature = "link")]
+    pub(crate) fn uncache(&self, name: &str) {
+        if let Some(ref cache) = self.name_cache {
+            cache.lock().unwrap().remove(name);
+        }
+    }
+
+    /// Resolves an interface index to the name of the interface.
+    #[cfg(any(feature = "route", feature = "status"))]
+    pub(crate) async fn link_name_by_index(&self, index: u32) -> Result<String> {
+        use crate::Error;
+
+        use futures::TryStreamExt;
+        use netlink_packet_route::link::LinkAttribute;
+
+        let link = self
+            .handle()
+            .link()
+            .get()
+            .match_index(index)
+            .execute()
+            .try_next()
+            .await?
+            .ok_or(Error::LinkNotFound(index.to_string()))?;
+
+        link.attributes
+            .into_iter()
+            .find_map(|attr| {
+                if let LinkAttribute::IfName(name) = attr {
+                    Some(name)
+                } else {
+                    None
+                }
+            })
+            .ok_or(Error::LinkNotFound(index.to_string()))
     }
 }
@@ -1,3 +1,4 @@
+use std::net::IpAddr;
 use std::{ffi, io};
 
 use thiserror::Error;
@@ -6,6 +7,28 @@ use thiserror::Error;
 pub enum Error {
     #[error("link {0} not found")]
     LinkNotFound(String),
+    #[error("link {0} has no MAC address")]
+    NoMacAddress(String),
+    #[error("link {0} has no MTU")]
+    NoMtu(String),
+    #[error("interface index {0} is already in use")]
+    IndexInUse(u32),
+    #[error("invalid bridge options: {0}")]
+    InvalidBridgeOpts(String),
+    #[error("tunnel {0} already exists")]
+    TunnelExists(String),
+    #[error("operation not permitted")]
+    PermissionDenied,
+    #[error("invalid tunnel options: {0}")]
+    InvalidTunnelOpts(String),
+    #[error("invalid route: {0}")]
+    InvalidRoute(String),
+    #[error("invalid prefix length {0}")]
+    InvalidPrefixLength(u8),
+    #[error("failed to add addresses: {}", fmt_failed(.0))]
+    AddressesFailed(Vec<(IpAddr, u8, rtnetlink::Error)>),
+    #[error("timed out")]
+    Timeout,
 
     #[error("link name contains nul bytes: {0}")]
     Nul(#[from] ffi::NulError),
@@ -16,3 +39,11 @@ pub enum Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+fn fmt_failed(failed: &[(IpAddr, u8, rtnetlink::Error)]) -> String {
+    failed
+        .iter()
+        .map(|(addr, prefix_len, e)| format!("{}/{} ({})", addr, prefix_len, e))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
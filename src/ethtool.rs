@@ -0,0 +1,187 @@
+//! Simple functions to configure interface offloads using the ethtool ioctl.
+
+use crate::Result;
+
+use std::ffi::{c_char, c_int, c_void, CString};
+use std::io;
+
+const SIOCETHTOOL: c_int = 0x8946;
+
+const ETHTOOL_GSTRINGS: u32 = 0x1b;
+const ETHTOOL_GSSET_INFO: u32 = 0x37;
+const ETHTOOL_SFEATURES: u32 = 0x3b;
+
+const ETH_SS_FEATURES: u32 = 4;
+const ETH_GSTRING_LEN: usize = 32;
+
+/// An offload feature of a network interface.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OffloadFeature {
+    /// Generic receive offload (`rx-gro`).
+    Gro,
+    /// Generic segmentation offload (`tx-generic-segmentation`).
+    Gso,
+    /// TCP segmentation offload for IPv4 and IPv6 (`tx-tcp*-segmentation`).
+    Tso,
+    /// Transmit checksum offload (`tx-checksum-*`).
+    TxChecksum,
+    /// Receive checksum offload (`rx-checksum`).
+    RxChecksum,
+}
+
+impl OffloadFeature {
+    fn names(&self) -> &'static [&'static str] {
+        match self {
+            Self::Gro => &["rx-gro"],
+            Self::Gso => &["tx-generic-segmentation"],
+            Self::Tso => &[
+                "tx-tcp-segmentation",
+                "tx-tcp-ecn-segmentation",
+                "tx-tcp-mangleid-segmentation",
+                "tx-tcp6-segmentation",
+            ],
+            Self::TxChecksum => &[
+                "tx-checksum-ipv4",
+                "tx-checksum-ip-generic",
+                "tx-checksum-ipv6",
+                "tx-checksum-fcoe-crc",
+                "tx-checksum-sctp",
+            ],
+            Self::RxChecksum => &["rx-checksum"],
+        }
+    }
+}
+
+/// Enables or disables an offload feature of an interface.
+///
+/// The feature is looked up in the `ETH_SS_FEATURES` string set of the interface
+/// and changed using `ETHTOOL_SFEATURES`. Features the driver doesn't allow
+/// to be changed are silently left as they are, consistent with `ethtool -K`.
+///
+/// # Arguments
+///
+/// * `link` - The name of the interface to be modified.
+/// * `feature` - The offload feature to be changed.
+/// * `on` - Whether the feature should be enabled.
+pub fn link_set_offload(link: &str, feature: OffloadFeature, on: bool) -> Result<()> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, libc::IPPROTO_IP) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let result = set_offload(fd, link, feature, on);
+
+    // Errors are safe to ignore because they don't affect the configuration
+    // but do leave the program in an inconsistent state.
+    unsafe {
+        libc::close(fd);
+    }
+
+    result
+}
+
+fn set_offload(fd: c_int, link: &str, feature: OffloadFeature, on: bool) -> Result<()> {
+    let names = feature_names(fd, link)?;
+
+    let size = names.len().div_ceil(32);
+    let mut blocks = vec![0u32; 2 * size];
+
+    let mut found = false;
+    for (i, name) in names.iter().enumerate() {
+        if feature.names().contains(&name.as_str()) {
+            let bit = 1 << (i % 32);
+
+            blocks[2 * (i / 32)] |= bit;
+            if on {
+                blocks[2 * (i / 32) + 1] |= bit;
+            }
+
+            found = true;
+        }
+    }
+
+    if !found {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{:?} offload not supported by {}", feature, link),
+        )
+        .into());
+    }
+
+    let mut sfeatures = vec![ETHTOOL_SFEATURES, size as u32];
+    sfeatures.extend(blocks);
+
+    ethtool_ioctl(fd, link, sfeatures.as_mut_ptr() as *mut c_void)
+}
+
+fn feature_names(fd: c_int, link: &str) -> Result<Vec<String>> {
+    let mut sset_info = SsetInfo {
+        cmd: ETHTOOL_GSSET_INFO,
+        reserved: 0,
+        sset_mask: 1 << ETH_SS_FEATURES,
+        data: 0,
+    };
+
+    ethtool_ioctl(fd, link, &mut sset_info as *mut _ as *mut c_void)?;
+
+    let len = if sset_info.sset_mask != 0 {
+        sset_info.data as usize
+    } else {
+        0
+    };
+
+    let mut gstrings = vec![0u8; 12 + len * ETH_GSTRING_LEN];
+    gstrings[0..4].copy_from_slice(&ETHTOOL_GSTRINGS.to_ne_bytes());
+    gstrings[4..8].copy_from_slice(&ETH_SS_FEATURES.to_ne_bytes());
+    gstrings[8..12].copy_from_slice(&(len as u32).to_ne_bytes());
+
+    ethtool_ioctl(fd, link, gstrings.as_mut_ptr() as *mut c_void)?;
+
+    let names = gstrings[12..]
+        .chunks(ETH_GSTRING_LEN)
+        .map(|name| {
+            let end = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+            String::from_utf8_lossy(&name[..end]).into_owned()
+        })
+        .collect();
+
+    Ok(names)
+}
+
+fn ethtool_ioctl(fd: c_int, link: &str, data: *mut c_void) -> Result<()> {
+    let ifname = CString::new(link)?;
+
+    #[allow(clippy::unnecessary_cast)]
+    let ifname_raw = unsafe { &*(ifname.as_bytes() as *const _ as *const [c_char]) };
+    let mut ifname_arr = [0; libc::IFNAMSIZ];
+    for (&i, o) in ifname_raw.iter().zip(ifname_arr.iter_mut()) {
+        *o = i;
+    }
+
+    let ifr = IfReqEthtool {
+        name: ifname_arr,
+        ifru_data: data,
+    };
+
+    if unsafe { libc::ioctl(fd, SIOCETHTOOL as _, &ifr) } < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+#[repr(C)]
+struct SsetInfo {
+    cmd: u32,
+    reserved: u32,
+    sset_mask: u64,
+    data: u32,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+struct IfReqEthtool {
+    name: [c_char; libc::IFNAMSIZ],
+    ifru_data: *mut c_void,
+}
@@ -4,10 +4,18 @@ pub use connection::*;
 mod error;
 pub use error::*;
 
+mod netns;
+
 #[cfg(feature = "addr")]
 pub mod addr;
+#[cfg(feature = "ethtool")]
+pub mod ethtool;
 #[cfg(feature = "status")]
 pub mod link;
+#[cfg(feature = "monitor")]
+pub mod monitor;
+#[cfg(all(feature = "addr", feature = "route", feature = "link"))]
+pub mod recipes;
 #[cfg(feature = "route")]
 pub mod route;
 #[cfg(feature = "tunnel")]
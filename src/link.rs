@@ -2,28 +2,124 @@
 
 use crate::{Connection, Error, Result};
 
+use std::collections::HashMap;
+#[cfg(feature = "link")]
+use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv6Addr};
+#[cfg(feature = "link")]
+use std::os::fd::RawFd;
 use std::time::Duration;
 
-use tokio::time::sleep;
+use tokio::time::{sleep, Instant};
 
-use futures::TryStreamExt;
-use netlink_packet_route::link::LinkFlag;
+#[cfg(feature = "link")]
+use futures::StreamExt;
+use futures::{future, TryStreamExt};
+#[cfg(feature = "link")]
+use netlink_packet_core::{NetlinkMessage, NetlinkPayload, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::address::{AddressAttribute, AddressMessage};
+use netlink_packet_route::link::{
+    AfSpecInet6, AfSpecUnspec, InfoKind, LinkAttribute, LinkFlag, LinkInfo as LinkInfoAttribute,
+    LinkLayerType, LinkMessage, State,
+};
+#[cfg(feature = "link")]
+use netlink_packet_route::link::{
+    InfoBridge, InfoData, InfoGreTap, InfoVeth, InfoVlan, InfoVrf, LinkProtocolDownReason,
+    VlanQosMapping,
+};
+#[cfg(feature = "link")]
+use netlink_packet_route::RouteNetlinkMessage;
+#[cfg(feature = "link")]
+use netlink_packet_utils::nla::DefaultNla;
+
+#[cfg(feature = "link")]
+const IF_LINK_MODE_DEFAULT: u8 = 0;
+#[cfg(feature = "link")]
+const IF_LINK_MODE_DORMANT: u8 = 1;
+
+#[cfg(feature = "link")]
+const IFLA_GRE_IFLAGS: u16 = 2;
+#[cfg(feature = "link")]
+const IFLA_GRE_OFLAGS: u16 = 3;
+#[cfg(feature = "link")]
+const IFLA_GRE_IKEY: u16 = 4;
+#[cfg(feature = "link")]
+const IFLA_GRE_OKEY: u16 = 5;
+#[cfg(feature = "link")]
+const IFLA_GRE_LOCAL: u16 = 6;
+#[cfg(feature = "link")]
+const IFLA_GRE_REMOTE: u16 = 7;
+#[cfg(feature = "link")]
+const GRE_KEY: u16 = 0x2000;
+
+/// STP and MAC learning options of a bridge.
+/// Options that are `None` are left unchanged.
+///
+/// The kernel stores these in units of 1/100 seconds,
+/// so the durations are truncated to that precision.
+#[cfg(feature = "link")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BridgeOpts {
+    /// How long a learned MAC address is kept in the forwarding database.
+    pub ageing_time: Option<Duration>,
+    /// The time spent in the listening and learning states. Must be 2-30 seconds if STP is enabled.
+    pub forward_delay: Option<Duration>,
+    /// The interval between STP hello packets. Must be 1-10 seconds.
+    pub hello_time: Option<Duration>,
+    /// The maximum age of received STP information. Must be 6-40 seconds.
+    pub max_age: Option<Duration>,
+}
+
+/// A consolidated view of an interface, see [`Connection::link_info`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LinkInfo {
+    /// The index of the interface.
+    pub index: u32,
+    /// The name of the interface.
+    pub name: String,
+    /// The hardware address of the interface, if it has a 6 byte one.
+    pub mac: Option<[u8; 6]>,
+    /// The MTU of the interface.
+    pub mtu: u32,
+    /// The flags of the interface, e.g. [`LinkFlag::Up`].
+    pub flags: Vec<LinkFlag>,
+    /// The RFC 2863 operational state of the interface.
+    pub oper_state: State,
+    /// The kind of a virtual interface, e.g. [`InfoKind::Vlan`],
+    /// or `None` for physical interfaces.
+    pub kind: Option<InfoKind>,
+    /// The name of the master interface (e.g. a bridge), if any.
+    pub master: Option<String>,
+    /// The IP addresses of the interface and their prefix lengths.
+    pub addrs: Vec<(IpAddr, u8)>,
+}
+
+/// An interface capability that can be queried using [`Connection::link_supports`].
+///
+/// Capabilities are derived from the interface dump
+/// and don't require any driver specific requests.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LinkCapability {
+    /// The interface has a non-zero link layer address,
+    /// i.e. it's not a layer 3 interface like WireGuard, PPP or loopback.
+    /// Determined by the `IFLA_ADDRESS` attribute.
+    L2Address,
+    /// The interface supports broadcast. Determined by the `IFF_BROADCAST` flag.
+    Broadcast,
+    /// The interface supports multicast. Determined by the `IFF_MULTICAST` flag.
+    Multicast,
+    /// VLAN interfaces can be created on top of the interface.
+    /// Determined by the Ethernet link layer type (`ARPHRD_ETHER`).
+    /// A few drivers still reject VLANs (`NETIF_F_VLAN_CHALLENGED`),
+    /// which isn't visible in the dump.
+    Vlan,
+}
 
 impl Connection {
     /// Brings an interface up or down.
     #[cfg(feature = "link")]
     pub async fn link_set(&self, link: String, state: bool) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
-
-        let id = link.header.index;
+        let id = self.resolve_index(&link).await?;
 
         match state {
             true => self.handle().link().set(id).up(),
@@ -42,39 +138,264 @@ impl Connection {
     /// This function fails if the interface doesn't exist
     /// or if any other `rtnetlink` error occurs.
     pub async fn link_is_up(&self, link: String) -> Result<bool> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+        let link = self.link_message(&link).await?;
 
-        let is_up = link.header.flags.iter().any(|flag| *flag == LinkFlag::Up);
+        let is_up = link.header.flags.contains(&LinkFlag::Up);
         Ok(is_up)
     }
 
+    /// Returns the RFC 2863 operational state of an interface, e.g. [`State::Up`]
+    /// or [`State::LowerLayerDown`] if it's administratively up but has no carrier.
+    ///
+    /// Unlike [`Connection::link_is_up`] this reflects whether the interface
+    /// is actually usable. Drivers that don't track the operational state
+    /// report [`State::Unknown`].
+    pub async fn link_oper_state(&self, link: String) -> Result<State> {
+        let link = self.link_message(&link).await?;
+
+        let state = link
+            .attributes
+            .into_iter()
+            .find_map(|attr| {
+                if let LinkAttribute::OperState(state) = attr {
+                    Some(state)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(State::Unknown);
+
+        Ok(state)
+    }
+
+    /// Reports whether an interface has a carrier, e.g. a cable is plugged in
+    /// (`IFF_LOWER_UP`). Interfaces that are administratively down
+    /// usually don't report a carrier.
+    pub async fn link_has_carrier(&self, link: String) -> Result<bool> {
+        let link = self.link_message(&link).await?;
+
+        let has_carrier = link.header.flags.contains(&LinkFlag::LowerUp);
+        Ok(has_carrier)
+    }
+
     /// Sets the MTU of an interface.
     #[cfg(feature = "link")]
     pub async fn link_set_mtu(&self, link: String, mtu: u32) -> Result<()> {
-        let link = self
-            .handle()
+        let id = self.resolve_index(&link).await?;
+
+        self.handle().link().set(id).mtu(mtu).execute().await?;
+        Ok(())
+    }
+
+    /// Sets the transmit queue length of an interface in packets.
+    ///
+    /// Queueing disciplines such as `pfifo_fast` use it as their default limit.
+    /// Interfaces without a queue, e.g. most virtual interfaces, accept but ignore it.
+    #[cfg(feature = "link")]
+    pub async fn link_set_txqueuelen(&self, link: String, len: u32) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().link().set(id);
+        req.message_mut()
+            .attributes
+            .push(LinkAttribute::TxQueueLen(len));
+
+        req.execute().await?;
+        Ok(())
+    }
+
+    /// Enables or disables promiscuous mode of an interface.
+    ///
+    /// This only controls the promiscuous flag set by the user (`ip link set promisc`).
+    /// The interface stays promiscuous while other users such as packet sockets
+    /// or bridges hold a reference, see [`Connection::link_promisc_count`].
+    #[cfg(feature = "link")]
+    pub async fn link_set_promisc(&self, link: String, on: bool) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        self.handle()
             .link()
-            .get()
-            .match_name(link.clone())
+            .set(id)
+            .promiscuous(on)
             .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+            .await?;
+        Ok(())
+    }
 
-        let id = link.header.index;
+    /// Sets the hardware (MAC) address of an interface.
+    ///
+    /// Many drivers refuse to change the address of an interface that is up
+    /// and fail with `EBUSY`, bring the interface down first in that case.
+    #[cfg(feature = "link")]
+    pub async fn link_set_mac(&self, link: String, mac: [u8; 6]) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
 
-        self.handle().link().set(id).mtu(mtu).execute().await?;
+        self.handle()
+            .link()
+            .set(id)
+            .address(mac.to_vec())
+            .execute()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sets the link-layer broadcast address of an interface.
+    ///
+    /// Most interfaces use `ff:ff:ff:ff:ff:ff` and rarely need this to be changed.
+    #[cfg(feature = "link")]
+    pub async fn link_set_broadcast(&self, link: String, bcast: [u8; 6]) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().link().set(id);
+        req.message_mut()
+            .attributes
+            .push(LinkAttribute::Broadcast(bcast.to_vec()));
+
+        req.execute().await?;
+        Ok(())
+    }
+
+    /// Sets the maximum size and number of segments of generic segmentation
+    /// offload (GSO) packets the kernel passes to an interface.
+    /// Limits that are `None` are left unchanged.
+    ///
+    /// Lowering these can help with tunnels or virtual interfaces
+    /// whose lower layers can't handle large GSO packets efficiently.
+    #[cfg(feature = "link")]
+    pub async fn link_set_gso_max(
+        &self,
+        link: String,
+        size: Option<u32>,
+        segs: Option<u32>,
+    ) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().link().set(id);
+        if let Some(size) = size {
+            req.message_mut()
+                .attributes
+                .push(LinkAttribute::GsoMaxSize(size));
+        }
+        if let Some(segs) = segs {
+            req.message_mut()
+                .attributes
+                .push(LinkAttribute::GsoMaxSegs(segs));
+        }
+
+        req.execute().await?;
+        Ok(())
+    }
+
+    /// Sets the IPv6 token of an interface. SLAAC then uses the token
+    /// as the interface identifier (host part) of autoconfigured addresses,
+    /// e.g. `::1` to get `<prefix>::1` for every advertised prefix.
+    ///
+    /// Only the lower 64 bits of the token are used.
+    /// The kernel rejects tokens on loopback interfaces
+    /// and on interfaces that don't accept router advertisements.
+    #[cfg(feature = "link")]
+    pub async fn link_set_ipv6_token(&self, link: String, token: Ipv6Addr) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().link().set(id);
+        req.message_mut()
+            .attributes
+            .push(LinkAttribute::AfSpecUnspec(vec![AfSpecUnspec::Inet6(
+                vec![AfSpecInet6::Token(token)],
+            )]));
+
+        req.execute().await?;
+        Ok(())
+    }
+
+    /// Marks an interface as dormant or releases it again.
+    ///
+    /// A dormant interface stays administratively up but has an operational
+    /// state of `DORMANT`, so the kernel doesn't consider it ready for traffic.
+    /// This is how 802.1X supplicants gate a port until authentication completes.
+    /// In contrast to [`Connection::link_set`] the interface keeps its
+    /// addresses and routes and can be released without reconfiguration.
+    ///
+    /// Marking an interface dormant switches it to the userspace-controlled
+    /// dormant link mode. Releasing it restores the default link mode
+    /// and sets the operational state to `UP`.
+    #[cfg(feature = "link")]
+    pub async fn link_set_dormant(&self, link: String, dormant: bool) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().link().set(id);
+        if dormant {
+            req.message_mut()
+                .attributes
+                .push(LinkAttribute::Mode(IF_LINK_MODE_DORMANT));
+            req.message_mut()
+                .attributes
+                .push(LinkAttribute::OperState(State::Dormant));
+        } else {
+            req.message_mut()
+                .attributes
+                .push(LinkAttribute::Mode(IF_LINK_MODE_DEFAULT));
+            req.message_mut()
+                .attributes
+                .push(LinkAttribute::OperState(State::Up));
+        }
+
+        req.execute().await?;
+        Ok(())
+    }
+
+    /// Sets or clears the protodown state of an interface.
+    ///
+    /// Protodown signals that an external protocol (e.g. MLAG or EVPN)
+    /// is holding the interface down regardless of its administrative state.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the interface to be modified.
+    /// * `down` - Whether the interface should be held down.
+    /// * `reason` - An optional bitmask of protodown reasons to set or clear.
+    ///
+    /// The kernel refuses to clear protodown while any reasons remain set.
+    #[cfg(feature = "link")]
+    pub async fn link_set_protodown(
+        &self,
+        link: String,
+        down: bool,
+        reason: Option<u32>,
+    ) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let mut req = self.handle().link().set(id);
+        req.message_mut()
+            .attributes
+            .push(LinkAttribute::ProtoDown(down.into()));
+
+        if let Some(reason) = reason {
+            req.message_mut()
+                .attributes
+                .push(LinkAttribute::ProtoDownReason(vec![
+                    LinkProtocolDownReason::Mask(reason),
+                    LinkProtocolDownReason::Value(if down { reason } else { 0 }),
+                ]));
+        }
+
+        req.execute().await?;
         Ok(())
     }
 
+    /// Reports whether an interface is held down by protodown.
+    pub async fn link_protodown(&self, link: String) -> Result<bool> {
+        let link = self.link_message(&link).await?;
+
+        let protodown = link
+            .attributes
+            .iter()
+            .any(|attr| matches!(attr, LinkAttribute::ProtoDown(state) if *state != 0));
+
+        Ok(protodown)
+    }
+
     /// Creates a VLAN interface on top of a parent interface.
     ///
     /// # Arguments
@@ -84,28 +405,138 @@ impl Connection {
     /// * `vlan_id` - The VLAN ID for tagging.
     #[cfg(feature = "link")]
     pub async fn link_add_vlan(&self, link: String, parent: String, vlan_id: u16) -> Result<()> {
-        let parent = self
+        let parent_id = self.resolve_index(&parent).await?;
+
+        self.handle()
+            .link()
+            .add()
+            .vlan(link, parent_id, vlan_id)
+            .execute()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the VLAN interface with a specific VLAN ID on top of a parent interface.
+    /// Fails with [`Error::LinkNotFound`] if no such VLAN interface exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - The name of the parent interface of the VLAN interface.
+    /// * `vlan_id` - The VLAN ID of the interface to be deleted.
+    #[cfg(feature = "link")]
+    pub async fn link_del_vlan(&self, parent: String, vlan_id: u16) -> Result<()> {
+        let parent_id = self.resolve_index(&parent).await?;
+
+        let links: Vec<LinkMessage> = self
             .handle()
             .link()
             .get()
-            .match_name(parent.clone())
             .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(parent))?;
+            .try_filter(|link| {
+                future::ready(
+                    link.attributes.contains(&LinkAttribute::Link(parent_id))
+                        && is_vlan(link, vlan_id),
+                )
+            })
+            .try_collect()
+            .await?;
+
+        let link = links
+            .first()
+            .ok_or(Error::LinkNotFound(format!("{}.{}", parent, vlan_id)))?;
 
-        let parent_id = parent.header.index;
+        for attr in &link.attributes {
+            if let LinkAttribute::IfName(name) = attr {
+                self.uncache(name);
+            }
+        }
 
         self.handle()
             .link()
-            .add()
-            .vlan(link, parent_id, vlan_id)
+            .del(link.header.index)
             .execute()
             .await?;
 
         Ok(())
     }
 
+    /// Adds a single entry to the egress QoS map of an existing VLAN interface,
+    /// mapping an skb priority to an 802.1p priority code point.
+    ///
+    /// The kernel merges the entry into the existing map
+    /// rather than replacing it. Entries for other priorities are kept
+    /// and an existing entry for the same priority is overwritten.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the VLAN interface to be modified.
+    /// * `from_prio` - The skb priority of outgoing packets.
+    /// * `to_pcp` - The 802.1p priority code point to tag these packets with.
+    #[cfg(feature = "link")]
+    pub async fn link_vlan_add_egress_map(
+        &self,
+        link: String,
+        from_prio: u32,
+        to_pcp: u32,
+    ) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        self.link_change_info(
+            id,
+            vec![
+                LinkInfoAttribute::Kind(InfoKind::Vlan),
+                LinkInfoAttribute::Data(InfoData::Vlan(vec![InfoVlan::EgressQos(vec![
+                    VlanQosMapping::Mapping(from_prio, to_pcp),
+                ])])),
+            ],
+        )
+        .await
+    }
+
+    /// Configures the STP and MAC learning options of a bridge.
+    ///
+    /// Fails with [`Error::InvalidBridgeOpts`] without contacting the kernel
+    /// if the hello time or maximum age are out of range
+    /// or if both the forward delay and the maximum age are specified
+    /// and violate the 802.1D requirement `2 * (forward_delay - 1s) >= max_age`.
+    /// The forward delay alone isn't range checked
+    /// because the kernel accepts any value while STP is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the bridge to be modified.
+    /// * `opts` - The options to be changed.
+    #[cfg(feature = "link")]
+    pub async fn link_set_bridge_opts(&self, link: String, opts: BridgeOpts) -> Result<()> {
+        opts.validate()?;
+
+        let id = self.resolve_index(&link).await?;
+
+        let mut data = Vec::new();
+        if let Some(ageing_time) = opts.ageing_time {
+            data.push(InfoBridge::AgeingTime(clock_t(ageing_time)));
+        }
+        if let Some(forward_delay) = opts.forward_delay {
+            data.push(InfoBridge::ForwardDelay(clock_t(forward_delay)));
+        }
+        if let Some(hello_time) = opts.hello_time {
+            data.push(InfoBridge::HelloTime(clock_t(hello_time)));
+        }
+        if let Some(max_age) = opts.max_age {
+            data.push(InfoBridge::MaxAge(clock_t(max_age)));
+        }
+
+        self.link_change_info(
+            id,
+            vec![
+                LinkInfoAttribute::Kind(InfoKind::Bridge),
+                LinkInfoAttribute::Data(InfoData::Bridge(data)),
+            ],
+        )
+        .await
+    }
+
     /// Creates a WireGuard interface.
     ///
     /// # Arguments
@@ -118,24 +549,270 @@ impl Connection {
         Ok(())
     }
 
-    /// Deletes an interface. Fails if the interface doesn't exist.
+    /// Creates a veth pair. Both ends are created in a single request.
+    /// The peer is brought up immediately, the first end stays down
+    /// until it's brought up using [`Connection::link_set`].
+    ///
+    /// Either end can later be moved to another network namespace,
+    /// e.g. to connect a container to the host.
     ///
     /// # Arguments
     ///
-    /// * `link` - The name of the interface to be deleted.
+    /// * `link` - The name of the first end of the veth pair.
+    /// * `peer` - The name of the second end of the veth pair.
     #[cfg(feature = "link")]
-    pub async fn link_delete(&self, link: String) -> Result<()> {
-        let link = self
-            .handle()
+    pub async fn link_add_veth(&self, link: String, peer: String) -> Result<()> {
+        self.handle()
             .link()
-            .get()
-            .match_name(link.clone())
+            .add()
+            .veth(link, peer)
             .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+            .await?;
+
+        Ok(())
+    }
+
+    /// Creates a veth pair with explicitly chosen interface indices.
+    /// Behaves like [`Connection::link_add_veth`] otherwise.
+    ///
+    /// The indices are requested through the `ifi_index` field
+    /// of the link messages, which requires Linux 3.7 or newer.
+    /// Older kernels ignore it and pick the indices themselves.
+    /// `IFLA_NEW_IFINDEX` is not used as the kernel only honors it
+    /// when moving an existing interface to another network namespace.
+    ///
+    /// Fails with [`Error::IndexInUse`] if one of the indices is already taken.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the first end of the veth pair.
+    /// * `peer` - The name of the second end of the veth pair.
+    /// * `index` - The interface index of the first end.
+    /// * `peer_index` - The interface index of the second end.
+    ///   Both indices are required as the kernel ignores the index of the first end
+    ///   unless the second one is set as well.
+    #[cfg(feature = "link")]
+    pub async fn link_add_veth_with_index(
+        &self,
+        link: String,
+        peer: String,
+        index: u32,
+        peer_index: u32,
+    ) -> Result<()> {
+        let mut req = self.handle().link().add().veth(link, peer);
+
+        // The first end is the nested peer message, see rtnetlink's veth helper.
+        req.message_mut().header.index = peer_index;
+        for attr in req.message_mut().attributes.iter_mut() {
+            if let LinkAttribute::LinkInfo(info) = attr {
+                for nla in info.iter_mut() {
+                    if let LinkInfoAttribute::Data(InfoData::Veth(InfoVeth::Peer(msg))) = nla {
+                        msg.header.index = index;
+                    }
+                }
+            }
+        }
+
+        match req.execute().await {
+            Ok(()) => Ok(()),
+            // The kernel reports a taken index as EBUSY for the first end
+            // and as EEXIST for the second one, which is also used for taken names.
+            Err(rtnetlink::Error::NetlinkError(e))
+                if e.raw_code() == -libc::EBUSY || e.raw_code() == -libc::EEXIST =>
+            {
+                for taken in [index, peer_index] {
+                    if self.link_name_by_index(taken).await.is_ok() {
+                        return Err(Error::IndexInUse(taken));
+                    }
+                }
+
+                Err(rtnetlink::Error::NetlinkError(e).into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Creates a bridge.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the bridge to be created.
+    #[cfg(feature = "link")]
+    pub async fn link_add_bridge(&self, link: String) -> Result<()> {
+        self.handle().link().add().bridge(link).execute().await?;
+
+        Ok(())
+    }
+
+    /// Creates a VRF (virtual routing and forwarding) device bound to a routing table.
+    /// Interfaces are added to the VRF using [`Connection::link_set_master`].
+    /// Routes of the enslaved interfaces are placed in the table of the VRF.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the VRF to be created.
+    /// * `table` - The routing table of the VRF.
+    #[cfg(feature = "link")]
+    pub async fn link_add_vrf(&self, link: String, table: u32) -> Result<()> {
+        let mut req = self.handle().link().add().name(link);
+        req.message_mut()
+            .attributes
+            .push(LinkAttribute::LinkInfo(vec![
+                LinkInfoAttribute::Kind(InfoKind::Vrf),
+                LinkInfoAttribute::Data(InfoData::Vrf(vec![InfoVrf::TableId(table)])),
+            ]));
+
+        req.execute().await?;
+
+        Ok(())
+    }
+
+    /// Enslaves an interface to a master interface, e.g. a bridge, bond or VRF.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the interface to be enslaved.
+    /// * `master` - The name of the master interface.
+    #[cfg(feature = "link")]
+    pub async fn link_set_master(&self, link: String, master: String) -> Result<()> {
+        let link = self.link_message(&link).await?;
+
+        let master = self.link_message(&master).await?;
 
         let id = link.header.index;
+        let master_id = master.header.index;
+
+        self.handle()
+            .link()
+            .set(id)
+            .controller(master_id)
+            .execute()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Detaches an interface from its master interface, if any.
+    #[cfg(feature = "link")]
+    pub async fn link_set_nomaster(&self, link: String) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        self.handle()
+            .link()
+            .set(id)
+            .nocontroller()
+            .execute()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Moves an interface to the network namespace of a process.
+    /// The interface is no longer visible to this connection afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the interface to be moved.
+    /// * `pid` - The process whose network namespace the interface is moved to.
+    #[cfg(feature = "link")]
+    pub async fn link_set_netns_pid(&self, link: String, pid: u32) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+        self.uncache(&link);
+
+        self.handle()
+            .link()
+            .set(id)
+            .setns_by_pid(pid)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// Moves an interface to the network namespace referred to by a file descriptor,
+    /// e.g. an open `/var/run/netns/<name>` or `/proc/<pid>/ns/net` file.
+    /// The interface is no longer visible to this connection afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the interface to be moved.
+    /// * `fd` - The file descriptor of the target network namespace.
+    ///   It only needs to stay open until this function returns.
+    #[cfg(feature = "link")]
+    pub async fn link_set_netns_fd(&self, link: String, fd: RawFd) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+        self.uncache(&link);
+
+        self.handle()
+            .link()
+            .set(id)
+            .setns_by_fd(fd)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// Creates a GRE TAP (Ethernet over GRE) interface.
+    ///
+    /// Unlike a GRE tunnel this is an Ethernet interface
+    /// that can be added to a bridge.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the GRE TAP interface to be created.
+    /// * `local` - The address of the local tunnel endpoint.
+    /// * `remote` - The address of the remote tunnel endpoint.
+    /// * `key` - The optional GRE key used in both directions.
+    #[cfg(feature = "link")]
+    pub async fn link_add_gretap(
+        &self,
+        link: String,
+        local: Ipv4Addr,
+        remote: Ipv4Addr,
+        key: Option<u32>,
+    ) -> Result<()> {
+        let mut data = vec![
+            InfoGreTap::Other(DefaultNla::new(IFLA_GRE_LOCAL, local.octets().to_vec())),
+            InfoGreTap::Other(DefaultNla::new(IFLA_GRE_REMOTE, remote.octets().to_vec())),
+        ];
+
+        if let Some(key) = key {
+            let flags = GRE_KEY.to_be_bytes().to_vec();
+            let key = key.to_be_bytes().to_vec();
+
+            data.push(InfoGreTap::Other(DefaultNla::new(
+                IFLA_GRE_IFLAGS,
+                flags.clone(),
+            )));
+            data.push(InfoGreTap::Other(DefaultNla::new(IFLA_GRE_OFLAGS, flags)));
+            data.push(InfoGreTap::Other(DefaultNla::new(
+                IFLA_GRE_IKEY,
+                key.clone(),
+            )));
+            data.push(InfoGreTap::Other(DefaultNla::new(IFLA_GRE_OKEY, key)));
+        }
+
+        let mut req = self.handle().link().add().name(link);
+        req.message_mut()
+            .attributes
+            .push(LinkAttribute::LinkInfo(vec![
+                LinkInfoAttribute::Kind(InfoKind::GreTap),
+                LinkInfoAttribute::Data(InfoData::GreTap(data)),
+            ]));
+
+        req.execute().await?;
+
+        Ok(())
+    }
+
+    /// Deletes an interface. Fails if the interface doesn't exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the interface to be deleted.
+    #[cfg(feature = "link")]
+    pub async fn link_delete(&self, link: String) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+        self.uncache(&link);
 
         self.handle().link().del(id).execute().await?;
 
@@ -143,27 +820,90 @@ impl Connection {
     }
 
     /// Waits for an interface to come up, including waiting for its creation.
+    /// Never times out, see [`Connection::link_wait_up_timeout`].
     pub async fn link_wait_up(&self, link: String) -> Result<()> {
-        while !self.link_exists(link.clone()).await? || !self.link_is_up(link.clone()).await? {
-            sleep(Duration::from_millis(200)).await;
-        }
+        self.link_wait_up_timeout(link, Duration::from_millis(200), Duration::MAX)
+            .await?;
 
         Ok(())
     }
 
+    /// Waits for an interface to come up, including waiting for its creation.
+    /// Returns `true` if the interface is up or `false` if the timeout elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - The name of the interface to wait for.
+    /// * `interval` - The time to wait between checks.
+    /// * `timeout` - The maximum time to wait for. [`Duration::MAX`] waits forever.
+    pub async fn link_wait_up_timeout(
+        &self,
+        link: String,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<bool> {
+        let deadline = Instant::now().checked_add(timeout);
+
+        loop {
+            if self.link_exists(link.clone()).await? && self.link_is_up(link.clone()).await? {
+                return Ok(true);
+            } else if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(false);
+            }
+
+            sleep(interval).await;
+        }
+    }
+
+    /// Waits for an interface to come up, including waiting for its creation.
+    /// Fails with [`Error::Timeout`] if the timeout elapses.
+    /// See [`Connection::link_wait_up_timeout`].
+    pub async fn link_wait_up_timeout_or_err(
+        &self,
+        link: String,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<()> {
+        if self.link_wait_up_timeout(link, interval, timeout).await? {
+            Ok(())
+        } else {
+            Err(Error::Timeout)
+        }
+    }
+
     /// Reports whether an interface exists.
+    ///
+    /// Only the network namespace of the connection is searched.
+    /// Alternative interface names are matched as well,
+    /// use [`Connection::link_exists_exact`] to ignore them.
     pub async fn link_exists(&self, link: String) -> Result<bool> {
-        let exists = self
+        let exists = self.link_index_opt(link).await?.is_some();
+        Ok(exists)
+    }
+
+    /// Reports whether an interface with exactly this name exists.
+    ///
+    /// Only the network namespace of the connection is searched,
+    /// interfaces of the same name in other namespaces are never reported.
+    /// Unlike [`Connection::link_exists`] this ignores alternative names
+    /// (`ip link property add dev ... altname ...`)
+    /// which the kernel also resolves when looking up interfaces by name.
+    pub async fn link_exists_exact(&self, link: String) -> Result<bool> {
+        match self
             .handle()
             .link()
             .get()
-            .match_name(link)
+            .match_name(link.clone())
             .execute()
             .try_next()
             .await
-            .is_ok();
-
-        Ok(exists)
+        {
+            Ok(msg) => {
+                Ok(msg.is_some_and(|msg| msg.attributes.contains(&LinkAttribute::IfName(link))))
+            }
+            Err(rtnetlink::Error::NetlinkError(e)) if e.raw_code() == -libc::ENODEV => Ok(false),
+            Err(e) => Err(e.into()),
+        }
     }
 
     /// Waits until an interface is created.
@@ -177,16 +917,460 @@ impl Connection {
 
     /// Returns the index of an interface.
     pub async fn link_index(&self, link: String) -> Result<u32> {
-        let link = self
+        self.resolve_index(&link).await
+    }
+
+    /// Returns the index of an interface or `None` if it doesn't exist.
+    ///
+    /// This combines [`Connection::link_exists`] and [`Connection::link_index`]
+    /// into a single request.
+    pub async fn link_index_opt(&self, link: String) -> Result<Option<u32>> {
+        match self.resolve_index(&link).await {
+            Ok(id) => Ok(Some(id)),
+            Err(Error::LinkNotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the name of the master (bridge, bond or VRF) of an interface
+    /// or `None` if the interface isn't enslaved.
+    pub async fn link_master(&self, link: String) -> Result<Option<String>> {
+        let link = self.link_message(&link).await?;
+
+        let master = link.attributes.iter().find_map(|attr| {
+            if let LinkAttribute::Controller(master) = *attr {
+                Some(master)
+            } else {
+                None
+            }
+        });
+
+        match master {
+            Some(master) => Ok(Some(self.link_name_by_index(master).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the promiscuity count of an interface.
+    ///
+    /// This is the number of references currently holding the interface
+    /// in promiscuous mode, e.g. packet sockets or bridge ports.
+    /// The interface is promiscuous if the count is non-zero.
+    pub async fn link_promisc_count(&self, link: String) -> Result<u32> {
+        let link = self.link_message(&link).await?;
+
+        let count = link
+            .attributes
+            .iter()
+            .find_map(|attr| {
+                if let LinkAttribute::Promiscuity(count) = *attr {
+                    Some(count)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Ok(count)
+    }
+
+    /// Returns the names of all interfaces indexed by their interface index.
+    ///
+    /// This only needs a single request, making it suitable for resolving
+    /// many interface indices, e.g. when rendering routes or addresses.
+    pub async fn link_index_map(&self) -> Result<HashMap<u32, String>> {
+        let map = self
             .handle()
             .link()
             .get()
-            .match_name(link.clone())
             .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+            .try_filter_map(|link| {
+                future::ready(Ok(link.attributes.into_iter().find_map(|attr| {
+                    if let LinkAttribute::IfName(name) = attr {
+                        Some((link.header.index, name))
+                    } else {
+                        None
+                    }
+                })))
+            })
+            .try_collect()
+            .await?;
+
+        Ok(map)
+    }
+
+    /// Returns the MTU of an interface.
+    ///
+    /// Fails with [`Error::NoMtu`] if the kernel doesn't report one,
+    /// which doesn't happen for regular interfaces.
+    pub async fn link_get_mtu(&self, link: String) -> Result<u32> {
+        let msg = self.link_message(&link).await?;
+
+        msg.attributes
+            .into_iter()
+            .find_map(|attr| {
+                if let LinkAttribute::Mtu(mtu) = attr {
+                    Some(mtu)
+                } else {
+                    None
+                }
+            })
+            .ok_or(Error::NoMtu(link))
+    }
+
+    /// Returns the range of MTUs the driver of an interface accepts
+    /// as a `(min, max)` tuple.
+    ///
+    /// Falls back to the IPv4 minimum of 68 and the maximum of 65535
+    /// for bounds the kernel doesn't report or reports as zero (unlimited).
+    pub async fn link_mtu_range(&self, link: String) -> Result<(u32, u32)> {
+        let link = self.link_message(&link).await?;
+
+        let mut range = (68, 65535);
+        for attr in link.attributes {
+            match attr {
+                LinkAttribute::MinMtu(min) if min != 0 => range.0 = min,
+                LinkAttribute::MaxMtu(max) if max != 0 => range.1 = max,
+                _ => {}
+            }
+        }
+
+        Ok(range)
+    }
+
+    /// Returns the maximum size and number of segments of generic segmentation
+    /// offload (GSO) packets of an interface as a `(size, segs)` tuple.
+    ///
+    /// Falls back to the kernel defaults of 65536 and 65535
+    /// for limits the kernel doesn't report.
+    pub async fn link_gso_max(&self, link: String) -> Result<(u32, u32)> {
+        let link = self.link_message(&link).await?;
+
+        let mut gso_max = (65536, 65535);
+        for attr in link.attributes {
+            match attr {
+                LinkAttribute::GsoMaxSize(size) => gso_max.0 = size,
+                LinkAttribute::GsoMaxSegs(segs) => gso_max.1 = segs,
+                _ => {}
+            }
+        }
+
+        Ok(gso_max)
+    }
+
+    /// Returns the ID of the network namespace the link peer of an interface
+    /// (e.g. the other end of a veth pair) lives in, or `None` if the
+    /// interface doesn't have a peer in a different namespace.
+    ///
+    /// The ID is relative to the network namespace of the connection.
+    /// The same namespace may have a different ID (or none) elsewhere.
+    pub async fn link_netnsid(&self, link: String) -> Result<Option<i32>> {
+        let link = self.link_message(&link).await?;
+
+        let netnsid = link.attributes.iter().find_map(|attr| {
+            if let LinkAttribute::NetnsId(netnsid) = *attr {
+                Some(netnsid)
+            } else {
+                None
+            }
+        });
+
+        Ok(netnsid)
+    }
+
+    /// Returns the hardware (MAC) address of an interface.
+    ///
+    /// Fails with [`Error::NoMacAddress`] if the interface doesn't have
+    /// a 6 byte hardware address, e.g. IP tunnels or WireGuard interfaces.
+    /// The loopback interface reports an all-zero address.
+    pub async fn link_get_mac(&self, link: String) -> Result<[u8; 6]> {
+        let msg = self.link_message(&link).await?;
+
+        msg.attributes
+            .into_iter()
+            .find_map(|attr| {
+                if let LinkAttribute::Address(mac) = attr {
+                    mac.try_into().ok()
+                } else {
+                    None
+                }
+            })
+            .ok_or(Error::NoMacAddress(link))
+    }
+
+    /// Returns a consolidated view of an interface similar to `ip addr show`.
+    ///
+    /// All information is gathered from a single link dump
+    /// and a single address dump.
+    pub async fn link_info(&self, link: String) -> Result<LinkInfo> {
+        let links: Vec<LinkMessage> = self.handle().link().get().execute().try_collect().await?;
+
+        let msg = links
+            .iter()
+            .find(|msg| {
+                msg.attributes
+                    .iter()
+                    .any(|attr| matches!(attr, LinkAttribute::IfName(name) if *name == link))
+            })
+            .ok_or(Error::LinkNotFound(link.clone()))?;
+
+        let mut info = LinkInfo {
+            index: msg.header.index,
+            name: link,
+            mac: None,
+            mtu: 0,
+            flags: msg.header.flags.clone(),
+            oper_state: State::Unknown,
+            kind: None,
+            master: None,
+            addrs: Vec::new(),
+        };
+
+        for attr in &msg.attributes {
+            match attr {
+                LinkAttribute::Address(mac) => info.mac = mac.as_slice().try_into().ok(),
+                LinkAttribute::Mtu(mtu) => info.mtu = *mtu,
+                LinkAttribute::OperState(state) => info.oper_state = *state,
+                LinkAttribute::LinkInfo(infos) => {
+                    info.kind = infos.iter().find_map(|info| {
+                        if let LinkInfoAttribute::Kind(kind) = info {
+                            Some(kind.clone())
+                        } else {
+                            None
+                        }
+                    })
+                }
+                LinkAttribute::Controller(index) => {
+                    info.master = links
+                        .iter()
+                        .find(|msg| msg.header.index == *index)
+                        .and_then(|msg| {
+                            msg.attributes.iter().find_map(|attr| {
+                                if let LinkAttribute::IfName(name) = attr {
+                                    Some(name.clone())
+                                } else {
+                                    None
+                                }
+                            })
+                        })
+                }
+                _ => {}
+            }
+        }
+
+        let addrs: Vec<AddressMessage> = self
+            .handle()
+            .address()
+            .get()
+            .set_link_index_filter(info.index)
+            .execute()
+            .try_collect()
+            .await?;
+
+        info.addrs = addrs
+            .into_iter()
+            .filter_map(|addr| {
+                let prefix_len = addr.header.prefix_len;
 
-        Ok(link.header.index)
+                // IFA_LOCAL is the local address of point-to-point interfaces,
+                // IFA_ADDRESS is the address of the peer in that case.
+                let local = addr.attributes.iter().find_map(|attr| {
+                    if let AddressAttribute::Local(ip) = *attr {
+                        Some(ip)
+                    } else {
+                        None
+                    }
+                });
+                let address = addr.attributes.iter().find_map(|attr| {
+                    if let AddressAttribute::Address(ip) = *attr {
+                        Some(ip)
+                    } else {
+                        None
+                    }
+                });
+
+                local.or(address).map(|ip| (ip, prefix_len))
+            })
+            .collect();
+
+        Ok(info)
     }
+
+    /// Returns the number of carrier transitions (up and down) of an interface.
+    ///
+    /// The counter is cumulative since the creation of the interface,
+    /// compare two readings to detect a flapping link.
+    pub async fn link_carrier_changes(&self, link: String) -> Result<u32> {
+        let link = self.link_message(&link).await?;
+
+        let carrier_changes = link
+            .attributes
+            .iter()
+            .find_map(|attr| {
+                if let LinkAttribute::CarrierChanges(carrier_changes) = *attr {
+                    Some(carrier_changes)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(0);
+
+        Ok(carrier_changes)
+    }
+
+    /// Returns the name of the root qdisc of an interface,
+    /// e.g. `"fq_codel"`, `"noqueue"` or `"mq"`,
+    /// or `None` if the kernel doesn't report one.
+    pub async fn link_qdisc(&self, link: String) -> Result<Option<String>> {
+        let link = self.link_message(&link).await?;
+
+        let qdisc = link.attributes.into_iter().find_map(|attr| {
+            if let LinkAttribute::Qdisc(qdisc) = attr {
+                Some(qdisc)
+            } else {
+                None
+            }
+        });
+
+        Ok(qdisc)
+    }
+
+    /// Returns the IPv6 token of an interface,
+    /// or `None` if no token is set or IPv6 is disabled on the interface.
+    pub async fn link_ipv6_token(&self, link: String) -> Result<Option<Ipv6Addr>> {
+        let link = self.link_message(&link).await?;
+
+        let token = link
+            .attributes
+            .iter()
+            .filter_map(|attr| {
+                if let LinkAttribute::AfSpecUnspec(af_spec) = attr {
+                    Some(af_spec)
+                } else {
+                    None
+                }
+            })
+            .flatten()
+            .filter_map(|af_spec| {
+                if let AfSpecUnspec::Inet6(inet6) = af_spec {
+                    Some(inet6)
+                } else {
+                    None
+                }
+            })
+            .flatten()
+            .find_map(|attr| {
+                if let AfSpecInet6::Token(token) = *attr {
+                    Some(token)
+                } else {
+                    None
+                }
+            });
+
+        Ok(token.filter(|token| !token.is_unspecified()))
+    }
+
+    /// Reports whether an interface has the specified capability.
+    /// See [`LinkCapability`] for how each capability is determined.
+    pub async fn link_supports(&self, link: String, capability: LinkCapability) -> Result<bool> {
+        let link = self.link_message(&link).await?;
+
+        let supported = match capability {
+            LinkCapability::L2Address => link.attributes.iter().any(|attr| {
+                if let LinkAttribute::Address(addr) = attr {
+                    addr.iter().any(|byte| *byte != 0)
+                } else {
+                    false
+                }
+            }),
+            LinkCapability::Broadcast => link.header.flags.contains(&LinkFlag::Broadcast),
+            LinkCapability::Multicast => link.header.flags.contains(&LinkFlag::Multicast),
+            LinkCapability::Vlan => link.header.link_layer_type == LinkLayerType::Ether,
+        };
+
+        Ok(supported)
+    }
+
+    /// Changes the kind specific options of an existing interface.
+    ///
+    /// These are ignored by RTM_SETLINK. They can only be changed
+    /// by an RTM_NEWLINK without the NLM_F_CREATE, NLM_F_EXCL
+    /// and NLM_F_REPLACE flags, which the rtnetlink crate can't send.
+    #[cfg(feature = "link")]
+    async fn link_change_info(&self, id: u32, info: Vec<LinkInfoAttribute>) -> Result<()> {
+        let mut message = LinkMessage::default();
+        message.header.index = id;
+        message.attributes.push(LinkAttribute::LinkInfo(info));
+
+        let mut req = NetlinkMessage::from(RouteNetlinkMessage::NewLink(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+        let mut response = self.handle().clone().request(req)?;
+        while let Some(msg) = response.next().await {
+            if let NetlinkPayload::Error(e) = msg.payload {
+                return Err(rtnetlink::Error::NetlinkError(e).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "link")]
+fn is_vlan(link: &LinkMessage, vlan_id: u16) -> bool {
+    link.attributes.iter().any(|attr| {
+        if let LinkAttribute::LinkInfo(info) = attr {
+            info.contains(&LinkInfoAttribute::Kind(InfoKind::Vlan))
+                && info.iter().any(|info| {
+                    if let LinkInfoAttribute::Data(InfoData::Vlan(vlan)) = info {
+                        vlan.contains(&InfoVlan::Id(vlan_id))
+                    } else {
+                        false
+                    }
+                })
+        } else {
+            false
+        }
+    })
+}
+
+#[cfg(feature = "link")]
+impl BridgeOpts {
+    fn validate(&self) -> Result<()> {
+        if let Some(hello_time) = self.hello_time {
+            if !(Duration::from_secs(1)..=Duration::from_secs(10)).contains(&hello_time) {
+                return Err(Error::InvalidBridgeOpts(format!(
+                    "hello time {:?} not within 1-10 seconds",
+                    hello_time
+                )));
+            }
+        }
+
+        if let Some(max_age) = self.max_age {
+            if !(Duration::from_secs(6)..=Duration::from_secs(40)).contains(&max_age) {
+                return Err(Error::InvalidBridgeOpts(format!(
+                    "max age {:?} not within 6-40 seconds",
+                    max_age
+                )));
+            }
+
+            if let Some(forward_delay) = self.forward_delay {
+                if 2 * forward_delay.saturating_sub(Duration::from_secs(1)) < max_age {
+                    return Err(Error::InvalidBridgeOpts(format!(
+                        "forward delay {:?} too short for max age {:?}",
+                        forward_delay, max_age
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a duration to the clock_t (1/100 seconds) representation
+/// of the bridge timers.
+#[cfg(feature = "link")]
+fn clock_t(duration: Duration) -> u32 {
+    (duration.as_millis() / 10).try_into().unwrap_or(u32::MAX)
 }
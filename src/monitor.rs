@@ -0,0 +1,296 @@
+//! Streams of notifications about changes to network interfaces.
+//!
+//! Each monitor opens its own netlink socket bound to the multicast groups
+//! it's interested in, separate from the socket used to send requests.
+//! The socket is closed when the stream is dropped.
+//!
+//! Notifications are only delivered while the stream is alive,
+//! changes made before it's created aren't reported.
+//! If the stream isn't consumed fast enough the kernel drops notifications
+//! and the stream ends. Callers that need a consistent view
+//! should create a new monitor and then re-read the current state.
+//!
+//! # Example
+//!
+//! Waiting for a global IPv6 address, e.g. from a delegated prefix,
+//! before setting up tunnels:
+//!
+//! ```no_run
+//! use std::net::IpAddr;
+//!
+//! use futures::StreamExt;
+//! use rsdsl_netlinklib::Connection;
+//!
+//! # async fn example() -> rsdsl_netlinklib::Result<()> {
+//! let conn = Connection::new().await?;
+//! let mut events = Box::pin(conn.monitor_addrs().await?);
+//!
+//! while let Some(event) = events.next().await {
+//!     let event = event?;
+//!
+//!     if let IpAddr::V6(addr) = event.addr {
+//!         if event.added && addr.segments()[0] & 0xe000 == 0x2000 {
+//!             println!("{}/{} on interface {}", addr, event.prefix_len, event.link_index);
+//!             break;
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "route")]
+use crate::route::{route4, route6, route_links, Route4, Route6, RouteType};
+use crate::{netns, Connection, Result};
+
+#[cfg(feature = "route")]
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::{future, Stream, StreamExt};
+use netlink_packet_core::{NetlinkMessage, NetlinkPayload};
+use netlink_packet_route::address::{AddressAttribute, AddressMessage};
+use netlink_packet_route::link::{LinkAttribute, LinkFlag, LinkMessage};
+#[cfg(feature = "route")]
+use netlink_packet_route::AddressFamily;
+use netlink_packet_route::RouteNetlinkMessage;
+use netlink_sys::{AsyncSocket, SocketAddr};
+use tokio::task::JoinHandle;
+
+/// A change to a network interface, see [`Connection::monitor_links`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LinkEvent {
+    /// The index of the interface.
+    pub index: u32,
+    /// The name of the interface.
+    pub name: String,
+    /// Whether the interface is administratively up.
+    pub up: bool,
+    /// Whether the interface has a carrier, e.g. a cable is plugged in.
+    pub carrier: bool,
+    /// Whether the interface was removed. The kernel doesn't distinguish
+    /// between newly created and changed interfaces,
+    /// both are reported with `removed` set to `false`.
+    pub removed: bool,
+}
+
+/// An address change, see [`Connection::monitor_addrs`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AddrEvent {
+    /// The index of the interface the address belongs to.
+    pub link_index: u32,
+    /// The IP address.
+    pub addr: IpAddr,
+    /// The prefix length of the address.
+    pub prefix_len: u8,
+    /// Whether the address was added or changed (`true`) or removed (`false`).
+    /// Changes include lifetime updates and the completion
+    /// of IPv6 duplicate address detection.
+    pub added: bool,
+}
+
+/// A route change, see [`Connection::monitor_routes`].
+///
+/// The name of the outgoing interface is resolved when the event is consumed.
+/// It's `None` if the interface has been removed in the meantime,
+/// e.g. for routes that were deleted along with their interface.
+/// Nexthops through removed interfaces are omitted for the same reason.
+#[cfg(feature = "route")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RouteEvent {
+    /// An IPv4 route was added or replaced.
+    Added4(Route4),
+    /// An IPv4 route was deleted.
+    Deleted4(Route4),
+    /// An IPv6 route was added or replaced.
+    Added6(Route6),
+    /// An IPv6 route was deleted.
+    Deleted6(Route6),
+}
+
+impl Connection {
+    /// Returns a stream of changes to network interfaces,
+    /// including their creation, removal and up/down or carrier changes.
+    ///
+    /// This opens a separate netlink socket bound to the `RTNLGRP_LINK`
+    /// multicast group, see the [module documentation](self).
+    pub async fn monitor_links(&self) -> Result<impl Stream<Item = Result<LinkEvent>>> {
+        Ok(
+            Subscription::new(libc::RTMGRP_LINK as u32, self.netns())?.filter_map(|msg| {
+                future::ready(match msg.payload {
+                    NetlinkPayload::InnerMessage(RouteNetlinkMessage::NewLink(link)) => {
+                        Some(Ok(link_event(link, false)))
+                    }
+                    NetlinkPayload::InnerMessage(RouteNetlinkMessage::DelLink(link)) => {
+                        Some(Ok(link_event(link, true)))
+                    }
+                    NetlinkPayload::Error(e) => Some(Err(rtnetlink::Error::NetlinkError(e).into())),
+                    _ => None,
+                })
+            }),
+        )
+    }
+
+    /// Returns a stream of IPv4 and IPv6 address additions and removals.
+    ///
+    /// This opens a separate netlink socket bound to the `RTNLGRP_IPV4_IFADDR`
+    /// and `RTNLGRP_IPV6_IFADDR` multicast groups, see the [module documentation](self).
+    pub async fn monitor_addrs(&self) -> Result<impl Stream<Item = Result<AddrEvent>>> {
+        let groups = libc::RTMGRP_IPV4_IFADDR | libc::RTMGRP_IPV6_IFADDR;
+
+        Ok(
+            Subscription::new(groups as u32, self.netns())?.filter_map(|msg| {
+                future::ready(match msg.payload {
+                    NetlinkPayload::InnerMessage(RouteNetlinkMessage::NewAddress(addr)) => {
+                        addr_event(addr, true).map(Ok)
+                    }
+                    NetlinkPayload::InnerMessage(RouteNetlinkMessage::DelAddress(addr)) => {
+                        addr_event(addr, false).map(Ok)
+                    }
+                    NetlinkPayload::Error(e) => Some(Err(rtnetlink::Error::NetlinkError(e).into())),
+                    _ => None,
+                })
+            }),
+        )
+    }
+
+    /// Returns a stream of IPv4 and IPv6 route additions and deletions
+    /// in all routing tables. The table of each route is included,
+    /// e.g. to only track default routes of the main table.
+    /// Changes to routes whose type isn't a [`RouteType`],
+    /// e.g. the local routes of addresses, aren't reported.
+    ///
+    /// This opens a separate netlink socket bound to the `RTNLGRP_IPV4_ROUTE`
+    /// and `RTNLGRP_IPV6_ROUTE` multicast groups, see the [module documentation](self).
+    /// The request socket of this connection is used to resolve
+    /// the names of outgoing interfaces.
+    #[cfg(feature = "route")]
+    pub async fn monitor_routes(&self) -> Result<impl Stream<Item = Result<RouteEvent>> + '_> {
+        let groups = libc::RTMGRP_IPV4_ROUTE | libc::RTMGRP_IPV6_ROUTE;
+
+        Ok(
+            Subscription::new(groups as u32, self.netns())?.filter_map(move |msg| async move {
+                let (route, added) = match msg.payload {
+                    NetlinkPayload::InnerMessage(RouteNetlinkMessage::NewRoute(route)) => {
+                        (route, true)
+                    }
+                    NetlinkPayload::InnerMessage(RouteNetlinkMessage::DelRoute(route)) => {
+                        (route, false)
+                    }
+                    NetlinkPayload::Error(e) => {
+                        return Some(Err(rtnetlink::Error::NetlinkError(e).into()))
+                    }
+                    _ => return None,
+                };
+
+                RouteType::from_kernel(route.header.kind)?;
+
+                let mut names = HashMap::new();
+                for index in route_links(&route) {
+                    if let Ok(name) = self.link_name_by_index(index).await {
+                        names.insert(index, name);
+                    }
+                }
+
+                let event = match (route.header.address_family, added) {
+                    (AddressFamily::Inet, true) => RouteEvent::Added4(route4(&route, &names)?),
+                    (AddressFamily::Inet, false) => RouteEvent::Deleted4(route4(&route, &names)?),
+                    (AddressFamily::Inet6, true) => RouteEvent::Added6(route6(&route, &names)?),
+                    (AddressFamily::Inet6, false) => RouteEvent::Deleted6(route6(&route, &names)?),
+                    _ => return None,
+                };
+
+                Some(Ok(event))
+            }),
+        )
+    }
+}
+
+/// The notifications received by a dedicated netlink socket.
+/// The connection task is aborted when the subscription is dropped.
+struct Subscription {
+    task: JoinHandle<()>,
+    messages: UnboundedReceiver<(NetlinkMessage<RouteNetlinkMessage>, SocketAddr)>,
+}
+
+impl Subscription {
+    /// Opens a netlink socket that is bound to the specified `RTMGRP_*` groups
+    /// inside the network namespace at `netns`, if any.
+    fn new(groups: u32, netns: Option<&Path>) -> Result<Self> {
+        let open = || {
+            let (mut conn, _, messages) = rtnetlink::new_connection()?;
+            conn.socket_mut()
+                .socket_mut()
+                .bind(&SocketAddr::new(0, groups))?;
+
+            Ok((conn, messages))
+        };
+
+        let (conn, messages) = match netns {
+            Some(path) => netns::with_netns(path, open)?,
+            None => open()?,
+        };
+
+        Ok(Self {
+            task: tokio::spawn(conn),
+            messages,
+        })
+    }
+}
+
+impl Stream for Subscription {
+    type Item = NetlinkMessage<RouteNetlinkMessage>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.messages
+            .poll_next_unpin(cx)
+            .map(|msg| msg.map(|(msg, _)| msg))
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn link_event(link: LinkMessage, removed: bool) -> LinkEvent {
+    LinkEvent {
+        index: link.header.index,
+        name: link
+            .attributes
+            .into_iter()
+            .find_map(|attr| {
+                if let LinkAttribute::IfName(name) = attr {
+                    Some(name)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default(),
+        up: link.header.flags.contains(&LinkFlag::Up),
+        carrier: link.header.flags.contains(&LinkFlag::LowerUp),
+        removed,
+    }
+}
+
+fn addr_event(addr: AddressMessage, added: bool) -> Option<AddrEvent> {
+    let ip = addr.attributes.into_iter().find_map(|attr| {
+        if let AddressAttribute::Address(ip) = attr {
+            Some(ip)
+        } else {
+            None
+        }
+    })?;
+
+    Some(AddrEvent {
+        link_index: addr.header.index,
+        addr: ip,
+        prefix_len: addr.header.prefix_len,
+        added,
+    })
+}
@@ -0,0 +1,38 @@
+//! Temporary network namespace switching for the calling thread.
+
+use crate::Result;
+
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+/// Runs a closure inside the network namespace at `path`
+/// and restores the original network namespace afterwards.
+///
+/// `setns(2)` only affects the calling thread. The closure must not hand work
+/// off to other threads (e.g. by awaiting on a multi-threaded runtime)
+/// because they remain in the original namespace.
+/// Other code running on the same thread in the meantime
+/// (e.g. signal handlers) observes the target namespace.
+pub(crate) fn with_netns<T, F>(path: &Path, f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    let orig = File::open("/proc/thread-self/ns/net")?;
+    let target = File::open(path)?;
+
+    if unsafe { libc::setns(target.as_raw_fd(), libc::CLONE_NEWNET) } < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let result = f();
+
+    // Failing to restore leaves the thread in the wrong namespace,
+    // which is worse than any error the closure may have returned.
+    if unsafe { libc::setns(orig.as_raw_fd(), libc::CLONE_NEWNET) } < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    result
+}
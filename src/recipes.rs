@@ -0,0 +1,47 @@
+//! Multi-step operations that combine several of the other modules.
+
+use crate::{Connection, Error, Result};
+
+impl Connection {
+    /// Removes an interface in the correct order to avoid dangling state.
+    ///
+    /// The sequence is:
+    ///
+    /// 1. Flush all routes of the interface.
+    /// 2. Flush all addresses of the interface.
+    /// 3. Bring the interface down.
+    /// 4. Delete the interface.
+    ///
+    /// Succeeds if the interface doesn't exist or disappears at any point,
+    /// e.g. because another process deleted it concurrently.
+    /// Honours dry-run mode, see [`Connection::dry_run`].
+    pub async fn link_decommission(&self, link: String) -> Result<()> {
+        if !self.link_exists(link.clone()).await? {
+            return Ok(());
+        }
+
+        tolerate_absent(self.route_flush(link.clone()).await)?;
+        tolerate_absent(self.address_flush(link.clone()).await)?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        tolerate_absent(self.link_set(link.clone(), false).await)?;
+        tolerate_absent(self.link_delete(link).await)?;
+
+        Ok(())
+    }
+}
+
+fn tolerate_absent(result: Result<()>) -> Result<()> {
+    match result {
+        Err(Error::LinkNotFound(_)) => Ok(()),
+        Err(Error::RtNetlink(rtnetlink::Error::NetlinkError(e)))
+            if e.raw_code() == -libc::ENODEV || e.raw_code() == -libc::ESRCH =>
+        {
+            Ok(())
+        }
+        result => result,
+    }
+}
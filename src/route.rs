@@ -2,97 +2,277 @@
 
 use crate::{Connection, Error, Result};
 
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
 
-use futures::{future, TryStreamExt};
-use netlink_packet_route::route::{RouteAttribute, RouteMessage, RouteScope};
-use rtnetlink::IpVersion;
+use tokio::time::{sleep, Instant};
 
-impl Connection {
-    /// Flushes all IPv4 routes from an interface.
-    pub async fn route_flush4(&self, link: String) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+use futures::{future, StreamExt, TryStreamExt};
+use netlink_packet_core::{NetlinkMessage, NetlinkPayload, NLM_F_ACK, NLM_F_CREATE, NLM_F_REQUEST};
+use netlink_packet_route::route::{
+    RouteAddress, RouteAttribute, RouteFlag, RouteHeader, RouteMessage, RouteMetric, RouteNextHop,
+    RouteProtocol, RouteScope, RouteType as RtnlRouteType, RouteVia,
+};
+use netlink_packet_route::{AddressFamily, RouteNetlinkMessage};
 
-        let id = link.header.index;
+pub use rtnetlink::IpVersion;
 
-        let routes: Vec<RouteMessage> = self
-            .handle()
-            .route()
-            .get(IpVersion::V4)
-            .execute()
-            .try_filter(|route| {
-                future::ready(
-                    if let Some(ifi) = route.attributes.iter().find_map(|attr| {
-                        if let RouteAttribute::Oif(oif) = *attr {
-                            Some(oif)
-                        } else {
-                            None
-                        }
-                    }) {
-                        ifi == id
-                    } else {
-                        false
-                    },
-                )
-            })
-            .try_collect()
-            .await?;
+/// An IPv4 route as reported by the kernel.
+///
+/// The default value is a default route in the main table.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Route4 {
+    /// The destination prefix of the route.
+    pub dst: Ipv4Addr,
+    /// The length of the destination prefix.
+    pub prefix_len: u8,
+    /// The gateway to forward traffic to, if any.
+    pub rtr: Option<Ipv4Addr>,
+    /// An IPv6 gateway to forward traffic to instead of `rtr` (`RTA_VIA`),
+    /// e.g. the link-local address of a BGP unnumbered (RFC 5549) peer.
+    /// Only one of `rtr` and `via` can be set.
+    pub via: Option<Ipv6Addr>,
+    /// The name of the outgoing interface.
+    /// `None` for routes without one, e.g. multipath routes.
+    pub link: Option<String>,
+    /// The table the route is stored in.
+    pub table: u32,
+    /// The metric (priority) of the route. Lower values are preferred.
+    pub metric: Option<u32>,
+    /// The nexthops of a multipath route.
+    /// Empty for single-path routes, which use `rtr` and `link` instead.
+    pub nexthops: Vec<NextHop4>,
+    /// The preferred source address for traffic using the route.
+    pub prefsrc: Option<Ipv4Addr>,
+    /// The metrics (`RTA_METRICS`) of the route, e.g. its MTU.
+    pub metrics: RouteMetrics,
+    /// The type of the route. Routes that aren't unicast routes
+    /// don't have a gateway, outgoing interface or nexthops.
+    pub kind: RouteType,
+}
 
-        for route in routes {
-            self.handle().route().del(route).execute().await?;
+/// An IPv6 route as reported by the kernel.
+///
+/// The default value is a default route in the main table.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Route6 {
+    /// The destination prefix of the route.
+    pub dst: Ipv6Addr,
+    /// The length of the destination prefix.
+    pub prefix_len: u8,
+    /// The gateway to forward traffic to, if any.
+    pub rtr: Option<Ipv6Addr>,
+    /// The name of the outgoing interface.
+    /// `None` for routes without one, e.g. multipath routes.
+    pub link: Option<String>,
+    /// The table the route is stored in.
+    pub table: u32,
+    /// The metric (priority) of the route. Lower values are preferred.
+    pub metric: Option<u32>,
+    /// The nexthops of a multipath route.
+    /// Empty for single-path routes, which use `rtr` and `link` instead.
+    pub nexthops: Vec<NextHop6>,
+    /// The preferred source address for traffic using the route.
+    pub prefsrc: Option<Ipv6Addr>,
+    /// The remaining lifetime of the route, e.g. of a default route
+    /// learned from a router advertisement. `None` for routes that don't expire.
+    ///
+    /// It's read from the cache information (`RTA_CACHEINFO`) of the route
+    /// and rounded down to whole seconds when adding the route.
+    pub expires: Option<Duration>,
+    /// The metrics (`RTA_METRICS`) of the route, e.g. its MTU.
+    pub metrics: RouteMetrics,
+    /// The type of the route. Routes that aren't unicast routes
+    /// don't have a gateway, outgoing interface or nexthops.
+    pub kind: RouteType,
+}
+
+impl Default for Route4 {
+    fn default() -> Self {
+        Self {
+            dst: Ipv4Addr::UNSPECIFIED,
+            prefix_len: 0,
+            rtr: None,
+            via: None,
+            link: None,
+            table: RouteHeader::RT_TABLE_MAIN.into(),
+            metric: None,
+            nexthops: Vec::new(),
+            prefsrc: None,
+            metrics: RouteMetrics::default(),
+            kind: RouteType::Unicast,
         }
+    }
+}
 
-        Ok(())
+impl Default for Route6 {
+    fn default() -> Self {
+        Self {
+            dst: Ipv6Addr::UNSPECIFIED,
+            prefix_len: 0,
+            rtr: None,
+            link: None,
+            table: RouteHeader::RT_TABLE_MAIN.into(),
+            metric: None,
+            nexthops: Vec::new(),
+            prefsrc: None,
+            expires: None,
+            metrics: RouteMetrics::default(),
+            kind: RouteType::Unicast,
+        }
     }
+}
 
-    /// Flushes all IPv6 routes from an interface.
-    pub async fn route_flush6(&self, link: String) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
+impl Route4 {
+    /// Checks that a unicast route has somewhere to forward traffic to.
+    fn check_next_hop(&self) -> Result<()> {
+        check_next_hop(
+            self.kind,
+            self.rtr.is_some() || self.via.is_some() || self.link.is_some(),
+            !self.nexthops.is_empty(),
+        )
+    }
+}
 
-        let id = link.header.index;
+impl Route6 {
+    /// Checks that a unicast route has somewhere to forward traffic to.
+    fn check_next_hop(&self) -> Result<()> {
+        check_next_hop(
+            self.kind,
+            self.rtr.is_some() || self.link.is_some(),
+            !self.nexthops.is_empty(),
+        )
+    }
+}
 
-        let routes: Vec<RouteMessage> = self
-            .handle()
-            .route()
-            .get(IpVersion::V6)
-            .execute()
-            .try_filter(|route| {
-                future::ready(
-                    if let Some(ifi) = route.attributes.iter().find_map(|attr| {
-                        if let RouteAttribute::Oif(oif) = *attr {
-                            Some(oif)
-                        } else {
-                            None
-                        }
-                    }) {
-                        ifi == id
-                    } else {
-                        false
-                    },
-                )
-            })
-            .try_collect()
-            .await?;
+/// An IPv4 default route of the main table including all of its nexthops.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultRoute {
+    /// The metric (priority) of the route. Lower values are preferred.
+    pub metric: Option<u32>,
+    /// The nexthops of the route. Multipath routes have more than one.
+    pub nexthops: Vec<NextHop4>,
+}
 
-        for route in routes {
-            self.handle().route().del(route).execute().await?;
+/// A single IPv4 nexthop of a route.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NextHop4 {
+    /// The gateway to forward traffic to, if any.
+    pub rtr: Option<Ipv4Addr>,
+    /// The name of the outgoing interface.
+    pub link: String,
+    /// The relative weight of the nexthop ranging from 1 to 256,
+    /// 1 for single-path routes.
+    pub weight: u16,
+}
+
+/// A single IPv6 nexthop of a route.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NextHop6 {
+    /// The gateway to forward traffic to, if any.
+    pub rtr: Option<Ipv6Addr>,
+    /// The name of the outgoing interface.
+    pub link: String,
+    /// The relative weight of the nexthop ranging from 1 to 256,
+    /// 1 for single-path routes.
+    pub weight: u16,
+}
+
+/// The metrics of a [`Route4`] or [`Route6`].
+/// Metrics that are `None` aren't set and use the defaults of the kernel.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RouteMetrics {
+    /// The MTU of the path to the destination.
+    pub mtu: Option<u32>,
+    /// The maximum TCP segment size to advertise to the destination.
+    pub advmss: Option<u32>,
+    /// The hop limit (TTL) of packets sent to the destination.
+    pub hoplimit: Option<u32>,
+    /// The initial TCP congestion window in packets.
+    pub initcwnd: Option<u32>,
+    /// The initial TCP receive window in packets.
+    pub initrwnd: Option<u32>,
+}
+
+/// The type of a [`Route4`] or [`Route6`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RouteType {
+    /// Forwards traffic to a gateway or a directly connected network.
+    #[default]
+    Unicast,
+    /// Silently drops traffic.
+    Blackhole,
+    /// Drops traffic and reports that the host is unreachable.
+    Unreachable,
+    /// Drops traffic and reports that communication is prohibited.
+    Prohibit,
+}
+
+impl RouteType {
+    /// Converts the type of a route message,
+    /// returning `None` for types that aren't supported.
+    pub(crate) fn from_kernel(kind: RtnlRouteType) -> Option<Self> {
+        match kind {
+            RtnlRouteType::Unicast => Some(Self::Unicast),
+            RtnlRouteType::BlackHole => Some(Self::Blackhole),
+            RtnlRouteType::Unreachable => Some(Self::Unreachable),
+            RtnlRouteType::Prohibit => Some(Self::Prohibit),
+            _ => None,
+        }
+    }
+}
+
+impl From<RouteType> for RtnlRouteType {
+    fn from(kind: RouteType) -> Self {
+        match kind {
+            RouteType::Unicast => Self::Unicast,
+            RouteType::Blackhole => Self::BlackHole,
+            RouteType::Unreachable => Self::Unreachable,
+            RouteType::Prohibit => Self::Prohibit,
         }
+    }
+}
+
+/// Constraints for [`Connection::route_flush_filtered`].
+/// Routes have to match all constraints that are `Some`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RouteFilter {
+    /// The IP version of the routes. `None` matches both IPv4 and IPv6.
+    pub version: Option<IpVersion>,
+    /// The name of the outgoing interface.
+    pub link: Option<String>,
+    /// The routing table, e.g. 254 for the main table.
+    pub table: Option<u32>,
+    /// The protocol (origin) of the routes, e.g. [`RouteProtocol::Dhcp`].
+    pub protocol: Option<RouteProtocol>,
+    /// The scope of the routes.
+    pub scope: Option<RouteScope>,
+}
+
+impl Connection {
+    /// Flushes all IPv4 routes from an interface.
+    pub async fn route_flush4(&self, link: String) -> Result<()> {
+        self.route_flush_filtered(RouteFilter {
+            version: Some(IpVersion::V4),
+            link: Some(link),
+            ..Default::default()
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Flushes all IPv6 routes from an interface.
+    pub async fn route_flush6(&self, link: String) -> Result<()> {
+        self.route_flush_filtered(RouteFilter {
+            version: Some(IpVersion::V6),
+            link: Some(link),
+            ..Default::default()
+        })
+        .await?;
 
         Ok(())
     }
@@ -105,6 +285,60 @@ impl Connection {
         Ok(())
     }
 
+    /// Flushes all routes matching every constraint of a [`RouteFilter`]
+    /// across all routing tables. Returns the number of deleted routes.
+    ///
+    /// Each address family is dumped once and the matching routes are deleted
+    /// afterwards. An empty filter flushes all IPv4 and IPv6 routes.
+    pub async fn route_flush_filtered(&self, filter: RouteFilter) -> Result<usize> {
+        let id = match filter.link {
+            Some(link) => Some(self.resolve_index(&link).await?),
+            None => None,
+        };
+
+        let versions = match filter.version {
+            Some(version) => vec![version],
+            None => vec![IpVersion::V4, IpVersion::V6],
+        };
+
+        let mut n = 0;
+        for version in versions {
+            let routes: Vec<RouteMessage> = self
+                .handle()
+                .route()
+                .get(version)
+                .execute()
+                .try_filter(|route| {
+                    future::ready(
+                        id.map_or(true, |id| route_oif(route) == Some(id))
+                            && filter
+                                .table
+                                .map_or(true, |table| route_table(route) == table)
+                            && filter
+                                .protocol
+                                .map_or(true, |protocol| route.header.protocol == protocol)
+                            && filter
+                                .scope
+                                .map_or(true, |scope| route.header.scope == scope),
+                    )
+                })
+                .try_collect()
+                .await?;
+
+            n += routes.len();
+
+            if self.is_dry_run() {
+                continue;
+            }
+
+            for route in routes {
+                self.handle().route().del(route).execute().await?;
+            }
+        }
+
+        Ok(n)
+    }
+
     /// Adds a simple IPv4 route with an optional gateway.
     pub async fn route_add4(
         &self,
@@ -113,17 +347,7 @@ impl Connection {
         rtr: Option<Ipv4Addr>,
         link: String,
     ) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
-
-        let id = link.header.index;
+        let id = self.resolve_index(&link).await?;
 
         let mut add = self
             .handle()
@@ -139,6 +363,10 @@ impl Connection {
             add = add.scope(RouteScope::Link);
         }
 
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
         add.execute().await?;
         Ok(())
     }
@@ -151,17 +379,7 @@ impl Connection {
         rtr: Option<Ipv6Addr>,
         link: String,
     ) -> Result<()> {
-        let link = self
-            .handle()
-            .link()
-            .get()
-            .match_name(link.clone())
-            .execute()
-            .try_next()
-            .await?
-            .ok_or(Error::LinkNotFound(link))?;
-
-        let id = link.header.index;
+        let id = self.resolve_index(&link).await?;
 
         let mut add = self
             .handle()
@@ -177,7 +395,1767 @@ impl Connection {
             add = add.scope(RouteScope::Link);
         }
 
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        add.execute().await?;
+        Ok(())
+    }
+
+    /// Adds an IPv4 route described by a [`Route4`].
+    ///
+    /// Unlike [`Connection::route_add4`] this supports other tables, route types,
+    /// priorities, preferred source addresses, IPv6 gateways, multipath routes
+    /// and [`RouteMetrics`].
+    /// If `nexthops` isn't empty a multipath route is added
+    /// and `rtr`, `via` and `link` are ignored. Routes that aren't unicast routes
+    /// ignore `rtr`, `via`, `link` and `nexthops` altogether.
+    ///
+    /// The route is checked like [`Connection::route_validate4`] does.
+    pub async fn route_add_full4(&self, route: Route4) -> Result<()> {
+        route.check_next_hop()?;
+        let message = self.route_message4(&route).await?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        let mut add = self.handle().route().add();
+        *add.message_mut() = message;
+        add.execute().await?;
+
+        Ok(())
+    }
+
+    /// Adds an IPv6 route described by a [`Route6`].
+    ///
+    /// Unlike [`Connection::route_add6`] this supports other tables, route types,
+    /// priorities, preferred source addresses, multipath routes, [`RouteMetrics`]
+    /// and expiring routes.
+    /// If `nexthops` isn't empty a multipath route is added
+    /// and `rtr` and `link` are ignored. Routes that aren't unicast routes
+    /// ignore `rtr`, `link` and `nexthops` altogether.
+    ///
+    /// The route is checked like [`Connection::route_validate6`] does.
+    pub async fn route_add_full6(&self, route: Route6) -> Result<()> {
+        route.check_next_hop()?;
+        let message = self.route_message6(&route).await?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        let mut add = self.handle().route().add();
+        *add.message_mut() = message;
         add.execute().await?;
+
+        Ok(())
+    }
+
+    /// Checks an IPv4 route for mistakes without adding it.
+    ///
+    /// Fails with [`Error::InvalidPrefixLength`] if `prefix_len` exceeds 32,
+    /// with [`Error::InvalidRoute`] if `dst` has bits set beyond the prefix,
+    /// if both `rtr` and `via` are set, if a unicast route has neither a gateway
+    /// nor an outgoing interface nor nexthops or if a nexthop weight is out of range,
+    /// and with [`Error::LinkNotFound`] if an interface doesn't exist.
+    /// Gateways of the wrong address family can't be expressed by [`Route4`].
+    pub async fn route_validate4(&self, route: &Route4) -> Result<()> {
+        route.check_next_hop()?;
+        self.route_message4(route).await?;
+
+        Ok(())
+    }
+
+    /// Checks an IPv6 route for mistakes without adding it.
+    ///
+    /// Fails with [`Error::InvalidPrefixLength`] if `prefix_len` exceeds 128,
+    /// with [`Error::InvalidRoute`] if `dst` has bits set beyond the prefix,
+    /// if a unicast route has neither a gateway nor an outgoing interface
+    /// nor nexthops or if a nexthop weight is out of range,
+    /// and with [`Error::LinkNotFound`] if an interface doesn't exist.
+    /// Gateways of the wrong address family can't be expressed by [`Route6`].
+    pub async fn route_validate6(&self, route: &Route6) -> Result<()> {
+        route.check_next_hop()?;
+        self.route_message6(route).await?;
+
+        Ok(())
+    }
+
+    /// Deletes an IPv4 route, e.g. one returned by [`Connection::routes4`].
+    ///
+    /// The route has to match the destination, table and type.
+    /// The gateway (`rtr` or `via`), interface, metric, nexthops
+    /// and preferred source address only have to match if they're specified.
+    /// The [`RouteMetrics`] are ignored.
+    pub async fn route_del4(&self, route: Route4) -> Result<()> {
+        let message = self.route_message4(&route).await?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        self.handle()
+            .route()
+            .del(del_message(message))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes an IPv6 route, e.g. one returned by [`Connection::routes6`].
+    ///
+    /// The route has to match the destination, table and type.
+    /// The gateway, interface, metric, nexthops and preferred source address
+    /// only have to match if they're specified. The [`RouteMetrics`] and `expires` are ignored.
+    pub async fn route_del6(&self, route: Route6) -> Result<()> {
+        let message = self.route_message6(&route).await?;
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        self.handle()
+            .route()
+            .del(del_message(message))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// Moves an IPv4 route to another table, e.g. one returned by [`Connection::routes4`].
+    ///
+    /// The route is added to `table` before it's deleted from its original table,
+    /// so traffic keeps being forwarded while it's moved. If adding it fails
+    /// the original route is kept. If deleting the original route fails
+    /// the copy in `table` is deleted again and the error is returned.
+    /// Like [`Connection::route_add_full4`] the copy uses the `static` protocol.
+    pub async fn route_move_table4(&self, route: Route4, table: u32) -> Result<()> {
+        let moved = Route4 {
+            table,
+            ..route.clone()
+        };
+        self.route_add_full4(moved.clone()).await?;
+
+        if let Err(e) = self.route_del4(route).await {
+            // The delete error is more useful than a failure to clean up.
+            let _ = self.route_del4(moved).await;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Moves an IPv6 route to another table, e.g. one returned by [`Connection::routes6`].
+    ///
+    /// The route is added to `table` before it's deleted from its original table,
+    /// so traffic keeps being forwarded while it's moved. If adding it fails
+    /// the original route is kept. If deleting the original route fails
+    /// the copy in `table` is deleted again and the error is returned.
+    /// Like [`Connection::route_add_full6`] the copy uses the `static` protocol.
+    pub async fn route_move_table6(&self, route: Route6, table: u32) -> Result<()> {
+        let moved = Route6 {
+            table,
+            ..route.clone()
+        };
+        self.route_add_full6(moved.clone()).await?;
+
+        if let Err(e) = self.route_del6(route).await {
+            // The delete error is more useful than a failure to clean up.
+            let _ = self.route_del6(moved).await;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Builds the message for adding or deleting a [`Route4`].
+    async fn route_message4(&self, route: &Route4) -> Result<RouteMessage> {
+        check_prefix(route.dst.into(), route.prefix_len)?;
+
+        let mut add = self
+            .handle()
+            .route()
+            .add()
+            .v4()
+            .destination_prefix(route.dst, route.prefix_len)
+            .kind(route.kind.into());
+        set_table(add.message_mut(), route.table);
+
+        if let Some(metric) = route.metric {
+            add = add.priority(metric);
+        }
+
+        if let Some(prefsrc) = route.prefsrc {
+            add = add.pref_source(prefsrc);
+        }
+
+        let metrics = metric_attributes(&route.metrics);
+        if !metrics.is_empty() {
+            add.message_mut()
+                .attributes
+                .push(RouteAttribute::Metrics(metrics));
+        }
+
+        // Routes that drop traffic don't forward it anywhere.
+        if route.kind != RouteType::Unicast {
+            return Ok(mem::take(add.message_mut()));
+        }
+
+        if route.nexthops.is_empty() {
+            if let Some(link) = &route.link {
+                add = add.output_interface(self.resolve_index(link).await?);
+            }
+
+            match (route.rtr, route.via) {
+                (Some(rtr), None) => add = add.gateway(rtr),
+                (None, Some(via)) => add
+                    .message_mut()
+                    .attributes
+                    .push(RouteAttribute::Via(RouteVia::Inet6(via))),
+                (None, None) => add = add.scope(RouteScope::Link),
+                (Some(_), Some(_)) => {
+                    return Err(Error::InvalidRoute(
+                        "route has both an IPv4 and an IPv6 gateway".to_string(),
+                    ))
+                }
+            }
+        } else {
+            let mut hops = Vec::with_capacity(route.nexthops.len());
+            for hop in &route.nexthops {
+                let index = self.resolve_index(&hop.link).await?;
+                hops.push(next_hop(
+                    index,
+                    hop.rtr.map(RouteAddress::Inet),
+                    hop.weight,
+                )?);
+            }
+
+            add.message_mut()
+                .attributes
+                .push(RouteAttribute::MultiPath(hops));
+        }
+
+        Ok(mem::take(add.message_mut()))
+    }
+
+    /// Builds the message for adding or deleting a [`Route6`].
+    async fn route_message6(&self, route: &Route6) -> Result<RouteMessage> {
+        check_prefix(route.dst.into(), route.prefix_len)?;
+
+        let mut add = self
+            .handle()
+            .route()
+            .add()
+            .v6()
+            .destination_prefix(route.dst, route.prefix_len)
+            .kind(route.kind.into());
+        set_table(add.message_mut(), route.table);
+
+        if let Some(metric) = route.metric {
+            add = add.priority(metric);
+        }
+
+        if let Some(prefsrc) = route.prefsrc {
+            add = add.pref_source(prefsrc);
+        }
+
+        if let Some(expires) = route.expires {
+            add.message_mut().attributes.push(RouteAttribute::Expires(
+                expires.as_secs().try_into().unwrap_or(u32::MAX),
+            ));
+        }
+
+        let metrics = metric_attributes(&route.metrics);
+        if !metrics.is_empty() {
+            add.message_mut()
+                .attributes
+                .push(RouteAttribute::Metrics(metrics));
+        }
+
+        // Routes that drop traffic don't forward it anywhere.
+        if route.kind != RouteType::Unicast {
+            return Ok(mem::take(add.message_mut()));
+        }
+
+        if route.nexthops.is_empty() {
+            if let Some(link) = &route.link {
+                add = add.output_interface(self.resolve_index(link).await?);
+            }
+
+            if let Some(rtr) = route.rtr {
+                add = add.gateway(rtr);
+            } else {
+                add = add.scope(RouteScope::Link);
+            }
+        } else {
+            let mut hops = Vec::with_capacity(route.nexthops.len());
+            for hop in &route.nexthops {
+                let index = self.resolve_index(&hop.link).await?;
+                hops.push(next_hop(
+                    index,
+                    hop.rtr.map(RouteAddress::Inet6),
+                    hop.weight,
+                )?);
+            }
+
+            add.message_mut()
+                .attributes
+                .push(RouteAttribute::MultiPath(hops));
+        }
+
+        Ok(mem::take(add.message_mut()))
+    }
+
+    /// Adds an IPv6 default route learned from a router advertisement.
+    ///
+    /// The route uses the `ra` protocol and expires after the router lifetime,
+    /// just like the default routes the kernel installs when processing
+    /// router advertisements itself. Calling this again for the same gateway
+    /// and interface refreshes the lifetime of the existing route.
+    /// Default routes through other gateways are kept. Because they share
+    /// the same metric the kernel lists them as nexthops of a multipath route.
+    ///
+    /// # Arguments
+    ///
+    /// * `gateway` - The (usually link-local) address of the router.
+    /// * `link` - The name of the interface the advertisement was received on.
+    /// * `lifetime` - The router lifetime from the advertisement.
+    ///   It's rounded down to whole seconds.
+    pub async fn route_add_ra_default6(
+        &self,
+        gateway: Ipv6Addr,
+        link: String,
+        lifetime: Duration,
+    ) -> Result<()> {
+        let id = self.resolve_index(&link).await?;
+
+        let expires = lifetime.as_secs().try_into().unwrap_or(u32::MAX);
+
+        let mut add = self
+            .handle()
+            .route()
+            .add()
+            .v6()
+            .destination_prefix(Ipv6Addr::UNSPECIFIED, 0)
+            .output_interface(id)
+            .gateway(gateway)
+            .protocol(RouteProtocol::Ra);
+        add.message_mut()
+            .attributes
+            .push(RouteAttribute::Expires(expires));
+
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        // Without NLM_F_EXCL the kernel keeps routes through other gateways
+        // and only updates the expiry of a route through the same gateway,
+        // reporting EEXIST. The rtnetlink crate always sets
+        // either NLM_F_EXCL or NLM_F_REPLACE.
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::NewRoute(mem::take(add.message_mut())));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE;
+
+        let mut response = self.handle().clone().request(req)?;
+        while let Some(msg) = response.next().await {
+            if let NetlinkPayload::Error(e) = msg.payload {
+                if e.raw_code() != -libc::EEXIST {
+                    return Err(rtnetlink::Error::NetlinkError(e).into());
+                }
+            }
+        }
+
         Ok(())
     }
+
+    /// Returns all IPv4 default routes of the main table, ordered by metric.
+    ///
+    /// Unlike a simple gateway lookup this preserves multipath routes,
+    /// reporting every nexthop with its interface and weight.
+    pub async fn route_default_detailed4(&self) -> Result<Vec<DefaultRoute>> {
+        let routes: Vec<RouteMessage> = self
+            .handle()
+            .route()
+            .get(IpVersion::V4)
+            .execute()
+            .try_filter(|route| {
+                future::ready(
+                    route.header.destination_prefix_length == 0
+                        && route.header.kind == RtnlRouteType::Unicast
+                        && route_table(route) == RouteHeader::RT_TABLE_MAIN.into(),
+                )
+            })
+            .try_collect()
+            .await?;
+
+        let mut defaults = Vec::new();
+        for route in routes {
+            let mut metric = None;
+            let mut rtr = None;
+            let mut oif = None;
+            let mut multipath = Vec::new();
+
+            for attr in route.attributes {
+                match attr {
+                    RouteAttribute::Priority(priority) => metric = Some(priority),
+                    RouteAttribute::Gateway(RouteAddress::Inet(gateway)) => rtr = Some(gateway),
+                    RouteAttribute::Oif(ifi) => oif = Some(ifi),
+                    RouteAttribute::MultiPath(hops) => multipath = hops,
+                    _ => {}
+                }
+            }
+
+            let mut nexthops = Vec::new();
+            if let Some(oif) = oif {
+                nexthops.push(NextHop4 {
+                    rtr,
+                    link: self.link_name_by_index(oif).await?,
+                    weight: 1,
+                });
+            }
+
+            for hop in multipath {
+                let rtr = hop.attributes.iter().find_map(|attr| {
+                    if let RouteAttribute::Gateway(RouteAddress::Inet(gateway)) = attr {
+                        Some(*gateway)
+                    } else {
+                        None
+                    }
+                });
+
+                nexthops.push(NextHop4 {
+                    rtr,
+                    link: self.link_name_by_index(hop.interface_index).await?,
+                    weight: u16::from(hop.hops) + 1,
+                });
+            }
+
+            defaults.push(DefaultRoute { metric, nexthops });
+        }
+
+        defaults.sort_by_key(|route| route.metric.unwrap_or_default());
+        Ok(defaults)
+    }
+
+    /// Returns the IDs of all routing tables that contain routes
+    /// of the specified IP version.
+    pub async fn route_tables_in_use(&self, version: IpVersion) -> Result<BTreeSet<u32>> {
+        let tables = self
+            .handle()
+            .route()
+            .get(version)
+            .execute()
+            .map_ok(|route| route_table(&route))
+            .try_collect()
+            .await?;
+
+        Ok(tables)
+    }
+
+    /// Deletes all IPv4 routes whose destination lies within a supernet,
+    /// including the supernet route itself. Returns the number of deleted routes.
+    ///
+    /// A route is contained in the supernet if its prefix length is greater
+    /// than or equal to `prefix_len` and its destination masked to `prefix_len`
+    /// equals the supernet masked to `prefix_len`.
+    ///
+    /// # Arguments
+    ///
+    /// * `supernet` - The supernet to delete the routes within.
+    /// * `prefix_len` - The length of the supernet prefix.
+    /// * `table` - The table to delete the routes from, or `None` for all tables
+    ///   including the local table.
+    pub async fn route_del_within4(
+        &self,
+        supernet: Ipv4Addr,
+        prefix_len: u8,
+        table: Option<u32>,
+    ) -> Result<usize> {
+        self.route_del_within(IpVersion::V4, supernet.into(), prefix_len, table)
+            .await
+    }
+
+    /// Deletes all IPv6 routes whose destination lies within a supernet,
+    /// including the supernet route itself. Returns the number of deleted routes.
+    ///
+    /// See [`Connection::route_del_within4`] for the containment test.
+    ///
+    /// # Arguments
+    ///
+    /// * `supernet` - The supernet to delete the routes within.
+    /// * `prefix_len` - The length of the supernet prefix.
+    /// * `table` - The table to delete the routes from, or `None` for all tables
+    ///   including the local table.
+    pub async fn route_del_within6(
+        &self,
+        supernet: Ipv6Addr,
+        prefix_len: u8,
+        table: Option<u32>,
+    ) -> Result<usize> {
+        self.route_del_within(IpVersion::V6, supernet.into(), prefix_len, table)
+            .await
+    }
+
+    async fn route_del_within(
+        &self,
+        version: IpVersion,
+        supernet: IpAddr,
+        prefix_len: u8,
+        table: Option<u32>,
+    ) -> Result<usize> {
+        let routes: Vec<RouteMessage> = self
+            .handle()
+            .route()
+            .get(version)
+            .execute()
+            .try_filter(|route| {
+                future::ready(
+                    route.header.destination_prefix_length >= prefix_len
+                        && is_within(route_dst(route), supernet, prefix_len)
+                        && table.map_or(true, |table| route_table(route) == table),
+                )
+            })
+            .try_collect()
+            .await?;
+
+        let n = routes.len();
+
+        if self.is_dry_run() {
+            return Ok(n);
+        }
+
+        for route in routes {
+            self.handle().route().del(route).execute().await?;
+        }
+
+        Ok(n)
+    }
+
+    /// Returns the IPv4 route the kernel would use to reach a destination,
+    /// or `None` if the destination is unreachable.
+    ///
+    /// This is the route as stored in the routing table (`ip route get fibmatch`),
+    /// e.g. the default route for destinations without a more specific route.
+    ///
+    /// Like [`Connection::routes4`] this also returns `None` if the route
+    /// is of a type [`Route4`] can't represent, e.g. the local route
+    /// of an address of the host itself.
+    pub async fn route_get4(&self, dst: Ipv4Addr) -> Result<Option<Route4>> {
+        let route = match self.route_get(dst.into(), true).await? {
+            Some(route) => route,
+            None => return Ok(None),
+        };
+
+        let mut names = HashMap::new();
+        self.route_link_names(&route, &mut names).await?;
+
+        Ok(route4(&route, &names))
+    }
+
+    /// Returns the IPv6 route the kernel would use to reach a destination,
+    /// or `None` if the destination is unreachable.
+    ///
+    /// This is the route as stored in the routing table (`ip route get fibmatch`),
+    /// e.g. the default route for destinations without a more specific route.
+    ///
+    /// Like [`Connection::routes6`] this also returns `None` if the route
+    /// is of a type [`Route6`] can't represent, e.g. the local route
+    /// of an address of the host itself.
+    pub async fn route_get6(&self, dst: Ipv6Addr) -> Result<Option<Route6>> {
+        let route = match self.route_get(dst.into(), true).await? {
+            Some(route) => route,
+            None => return Ok(None),
+        };
+
+        let mut names = HashMap::new();
+        self.route_link_names(&route, &mut names).await?;
+
+        Ok(route6(&route, &names))
+    }
+
+    /// Returns the path MTU to an IPv4 destination as used by the kernel,
+    /// or `None` if the destination is unreachable or the MTU
+    /// of the outgoing interface applies.
+    ///
+    /// Unlike [`Connection::route_get4`] this reads the route cache entry
+    /// for the destination (`ip route get`), which includes a path MTU
+    /// learned from ICMP "fragmentation needed" messages
+    /// or the MTU of the [`RouteMetrics`] of the route.
+    /// Cache entries are ephemeral: a path MTU is only learned
+    /// after sending traffic to the destination and expires
+    /// after `net.ipv4.route.mtu_expires` seconds (10 minutes by default).
+    pub async fn route_pmtu4(&self, dst: Ipv4Addr) -> Result<Option<u32>> {
+        Ok(self
+            .route_get(dst.into(), false)
+            .await?
+            .and_then(|route| route_metrics(&route).mtu))
+    }
+
+    /// Returns the path MTU to an IPv6 destination as used by the kernel,
+    /// or `None` if the destination is unreachable or the MTU
+    /// of the outgoing interface applies.
+    ///
+    /// Unlike [`Connection::route_get6`] this reads the route cache entry
+    /// for the destination (`ip route get`), which includes a path MTU
+    /// learned from ICMPv6 "packet too big" messages
+    /// or the MTU of the [`RouteMetrics`] of the route.
+    /// Cache entries are ephemeral: a path MTU is only learned
+    /// after sending traffic to the destination and expires
+    /// after `net.ipv6.route.mtu_expires` seconds (10 minutes by default).
+    pub async fn route_pmtu6(&self, dst: Ipv6Addr) -> Result<Option<u32>> {
+        Ok(self
+            .route_get(dst.into(), false)
+            .await?
+            .and_then(|route| route_metrics(&route).mtu))
+    }
+
+    /// Returns the IPv4 routes of a table, or of all tables if `table` is `None`.
+    ///
+    /// Route types other than those of [`RouteType`], e.g. the local
+    /// and broadcast routes of the local table, are omitted
+    /// because [`Route4`] can't represent them.
+    pub async fn routes4(&self, table: Option<u32>) -> Result<Vec<Route4>> {
+        let (routes, names) = self.routes(IpVersion::V4, table).await?;
+        Ok(routes
+            .iter()
+            .filter_map(|route| route4(route, &names))
+            .collect())
+    }
+
+    /// Returns the IPv6 routes of a table, or of all tables if `table` is `None`.
+    ///
+    /// Route types other than those of [`RouteType`], e.g. the local
+    /// and multicast routes of the local table, are omitted
+    /// because [`Route6`] can't represent them.
+    pub async fn routes6(&self, table: Option<u32>) -> Result<Vec<Route6>> {
+        let (routes, names) = self.routes(IpVersion::V6, table).await?;
+        Ok(routes
+            .iter()
+            .filter_map(|route| route6(route, &names))
+            .collect())
+    }
+
+    /// Dumps the routes of a table that [`RouteType`] can represent
+    /// along with the names of the interfaces they refer to.
+    async fn routes(
+        &self,
+        version: IpVersion,
+        table: Option<u32>,
+    ) -> Result<(Vec<RouteMessage>, HashMap<u32, String>)> {
+        let routes: Vec<RouteMessage> = self
+            .handle()
+            .route()
+            .get(version)
+            .execute()
+            .try_filter(|route| {
+                future::ready(
+                    RouteType::from_kernel(route.header.kind).is_some()
+                        && table.map_or(true, |table| route_table(route) == table),
+                )
+            })
+            .try_collect()
+            .await?;
+
+        // Many routes usually share few interfaces.
+        let mut names = HashMap::new();
+        for route in &routes {
+            self.route_link_names(route, &mut names).await?;
+        }
+
+        Ok((routes, names))
+    }
+
+    /// Resolves the names of all interfaces a route refers to
+    /// that aren't in `names` yet, see [`route_links`].
+    async fn route_link_names(
+        &self,
+        route: &RouteMessage,
+        names: &mut HashMap<u32, String>,
+    ) -> Result<()> {
+        for index in route_links(route) {
+            if let Entry::Vacant(entry) = names.entry(index) {
+                entry.insert(self.link_name_by_index(index).await?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends a non-dump `RTM_GETROUTE` request for a destination.
+    /// The rtnetlink crate always sets `NLM_F_DUMP` which ignores the destination.
+    /// If `fib_match` is set the kernel returns the matching route of the routing table,
+    /// otherwise the route cache entry for the destination.
+    async fn route_get(&self, dst: IpAddr, fib_match: bool) -> Result<Option<RouteMessage>> {
+        let mut message = RouteMessage::default();
+        if fib_match {
+            message.header.flags.push(RouteFlag::FibMatch);
+        }
+        match dst {
+            IpAddr::V4(dst) => {
+                message.header.address_family = AddressFamily::Inet;
+                message.header.destination_prefix_length = 32;
+                message
+                    .attributes
+                    .push(RouteAttribute::Destination(RouteAddress::Inet(dst)));
+            }
+            IpAddr::V6(dst) => {
+                message.header.address_family = AddressFamily::Inet6;
+                message.header.destination_prefix_length = 128;
+                message
+                    .attributes
+                    .push(RouteAttribute::Destination(RouteAddress::Inet6(dst)));
+            }
+        }
+
+        let mut req = NetlinkMessage::from(RouteNetlinkMessage::GetRoute(message));
+        req.header.flags = NLM_F_REQUEST;
+
+        let mut response = self.handle().clone().request(req)?;
+        while let Some(msg) = response.next().await {
+            match msg.payload {
+                NetlinkPayload::InnerMessage(RouteNetlinkMessage::NewRoute(route)) => {
+                    return Ok(Some(route))
+                }
+                NetlinkPayload::Error(e)
+                    if e.raw_code() == -libc::ENETUNREACH
+                        || e.raw_code() == -libc::EHOSTUNREACH =>
+                {
+                    return Ok(None)
+                }
+                NetlinkPayload::Error(e) => return Err(rtnetlink::Error::NetlinkError(e).into()),
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the IDs of all tables containing an IPv4 route
+    /// to exactly the given destination prefix, in ascending order.
+    ///
+    /// Table IDs above 255 are read from the `RTA_TABLE` attribute.
+    pub async fn route_find_table4(&self, dst: Ipv4Addr, prefix_len: u8) -> Result<Vec<u32>> {
+        self.route_find_table(IpVersion::V4, dst.into(), prefix_len)
+            .await
+    }
+
+    /// Returns the IDs of all tables containing an IPv6 route
+    /// to exactly the given destination prefix, in ascending order.
+    ///
+    /// Table IDs above 255 are read from the `RTA_TABLE` attribute.
+    pub async fn route_find_table6(&self, dst: Ipv6Addr, prefix_len: u8) -> Result<Vec<u32>> {
+        self.route_find_table(IpVersion::V6, dst.into(), prefix_len)
+            .await
+    }
+
+    async fn route_find_table(
+        &self,
+        version: IpVersion,
+        dst: IpAddr,
+        prefix_len: u8,
+    ) -> Result<Vec<u32>> {
+        let tables: BTreeSet<u32> = self
+            .handle()
+            .route()
+            .get(version)
+            .execute()
+            .try_filter(|route| {
+                future::ready(
+                    route.header.destination_prefix_length == prefix_len && route_dst(route) == dst,
+                )
+            })
+            .map_ok(|route| route_table(&route))
+            .try_collect()
+            .await?;
+
+        Ok(tables.into_iter().collect())
+    }
+
+    /// Waits for an IPv4 route to be installed, e.g. by another daemon.
+    /// Returns `true` if the route exists or `false` if the timeout elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst` - The destination prefix of the route.
+    /// * `prefix_len` - The length of the destination prefix.
+    /// * `table` - The table the route has to be in, or `None` for any table.
+    /// * `interval` - The time to wait between checks.
+    /// * `timeout` - The maximum time to wait for. [`Duration::MAX`] waits forever.
+    pub async fn route_wait4(
+        &self,
+        dst: Ipv4Addr,
+        prefix_len: u8,
+        table: Option<u32>,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<bool> {
+        self.route_wait(
+            IpVersion::V4,
+            dst.into(),
+            prefix_len,
+            table,
+            interval,
+            timeout,
+        )
+        .await
+    }
+
+    /// Waits for an IPv6 route to be installed, e.g. by another daemon.
+    /// Returns `true` if the route exists or `false` if the timeout elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst` - The destination prefix of the route.
+    /// * `prefix_len` - The length of the destination prefix.
+    /// * `table` - The table the route has to be in, or `None` for any table.
+    /// * `interval` - The time to wait between checks.
+    /// * `timeout` - The maximum time to wait for. [`Duration::MAX`] waits forever.
+    pub async fn route_wait6(
+        &self,
+        dst: Ipv6Addr,
+        prefix_len: u8,
+        table: Option<u32>,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<bool> {
+        self.route_wait(
+            IpVersion::V6,
+            dst.into(),
+            prefix_len,
+            table,
+            interval,
+            timeout,
+        )
+        .await
+    }
+
+    async fn route_wait(
+        &self,
+        version: IpVersion,
+        dst: IpAddr,
+        prefix_len: u8,
+        table: Option<u32>,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<bool> {
+        let deadline = Instant::now().checked_add(timeout);
+
+        loop {
+            let exists = self
+                .handle()
+                .route()
+                .get(version.clone())
+                .execute()
+                .try_filter(|route| {
+                    future::ready(
+                        route.header.destination_prefix_length == prefix_len
+                            && route_dst(route) == dst
+                            && table.map_or(true, |table| route_table(route) == table),
+                    )
+                })
+                .try_next()
+                .await?
+                .is_some();
+
+            if exists {
+                return Ok(true);
+            } else if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(false);
+            }
+
+            sleep(interval).await;
+        }
+    }
+}
+
+/// Converts a route message of the IPv4 family. `names` maps the indices
+/// of the interfaces the route refers to to their names, see [`route_links`].
+/// Nexthops whose interface isn't in `names` are omitted.
+/// Returns `None` if the type of the route isn't a [`RouteType`].
+pub(crate) fn route4(route: &RouteMessage, names: &HashMap<u32, String>) -> Option<Route4> {
+    Some(Route4 {
+        dst: match route_dst(route) {
+            IpAddr::V4(dst) => dst,
+            IpAddr::V6(_) => Ipv4Addr::UNSPECIFIED,
+        },
+        prefix_len: route.header.destination_prefix_length,
+        rtr: match route_gateway(route) {
+            Some(IpAddr::V4(rtr)) => Some(rtr),
+            _ => None,
+        },
+        via: route.attributes.iter().find_map(|attr| {
+            if let RouteAttribute::Via(RouteVia::Inet6(via)) = attr {
+                Some(*via)
+            } else {
+                None
+            }
+        }),
+        link: route_oif(route).and_then(|oif| names.get(&oif).cloned()),
+        table: route_table(route),
+        metric: route_metric(route),
+        nexthops: route_multipath(route, names, |rtr, link, weight| NextHop4 {
+            rtr: match rtr {
+                Some(IpAddr::V4(rtr)) => Some(rtr),
+                _ => None,
+            },
+            link,
+            weight,
+        }),
+        prefsrc: match route_prefsrc(route) {
+            Some(IpAddr::V4(prefsrc)) => Some(prefsrc),
+            _ => None,
+        },
+        metrics: route_metrics(route),
+        kind: RouteType::from_kernel(route.header.kind)?,
+    })
+}
+
+/// Converts a route message of the IPv6 family. `names` maps the indices
+/// of the interfaces the route refers to to their names, see [`route_links`].
+/// Nexthops whose interface isn't in `names` are omitted.
+/// Returns `None` if the type of the route isn't a [`RouteType`].
+pub(crate) fn route6(route: &RouteMessage, names: &HashMap<u32, String>) -> Option<Route6> {
+    Some(Route6 {
+        dst: match route_dst(route) {
+            IpAddr::V6(dst) => dst,
+            IpAddr::V4(_) => Ipv6Addr::UNSPECIFIED,
+        },
+        prefix_len: route.header.destination_prefix_length,
+        rtr: match route_gateway(route) {
+            Some(IpAddr::V6(rtr)) => Some(rtr),
+            _ => None,
+        },
+        link: route_oif(route).and_then(|oif| names.get(&oif).cloned()),
+        table: route_table(route),
+        metric: route_metric(route),
+        nexthops: route_multipath(route, names, |rtr, link, weight| NextHop6 {
+            rtr: match rtr {
+                Some(IpAddr::V6(rtr)) => Some(rtr),
+                _ => None,
+            },
+            link,
+            weight,
+        }),
+        prefsrc: match route_prefsrc(route) {
+            Some(IpAddr::V6(prefsrc)) => Some(prefsrc),
+            _ => None,
+        },
+        expires: route_expires(route),
+        metrics: route_metrics(route),
+        kind: RouteType::from_kernel(route.header.kind)?,
+    })
+}
+
+/// Returns the outgoing interface index of a route, if any.
+/// Multipath routes don't have one.
+pub(crate) fn route_oif(route: &RouteMessage) -> Option<u32> {
+    route.attributes.iter().find_map(|attr| {
+        if let RouteAttribute::Oif(oif) = *attr {
+            Some(oif)
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the indices of all interfaces a route refers to,
+/// i.e. its outgoing interface and the interfaces of its nexthops.
+pub(crate) fn route_links(route: &RouteMessage) -> Vec<u32> {
+    let mut links: Vec<u32> = route_oif(route).into_iter().collect();
+    for attr in &route.attributes {
+        if let RouteAttribute::MultiPath(hops) = attr {
+            links.extend(hops.iter().map(|hop| hop.interface_index));
+        }
+    }
+
+    links
+}
+
+/// Returns the nexthops of a multipath route, converting each of them
+/// from its gateway, interface name and weight using `nexthop`.
+/// Nexthops whose interface isn't in `names` are omitted.
+fn route_multipath<H>(
+    route: &RouteMessage,
+    names: &HashMap<u32, String>,
+    nexthop: impl Fn(Option<IpAddr>, String, u16) -> H,
+) -> Vec<H> {
+    route
+        .attributes
+        .iter()
+        .filter_map(|attr| {
+            if let RouteAttribute::MultiPath(hops) = attr {
+                Some(hops)
+            } else {
+                None
+            }
+        })
+        .flatten()
+        .filter_map(|hop| {
+            let rtr = hop.attributes.iter().find_map(|attr| match attr {
+                RouteAttribute::Gateway(RouteAddress::Inet(rtr)) => Some(IpAddr::V4(*rtr)),
+                RouteAttribute::Gateway(RouteAddress::Inet6(rtr)) => Some(IpAddr::V6(*rtr)),
+                _ => None,
+            });
+
+            Some(nexthop(
+                rtr,
+                names.get(&hop.interface_index)?.clone(),
+                u16::from(hop.hops) + 1,
+            ))
+        })
+        .collect()
+}
+
+/// Builds a nexthop of a multipath route.
+/// Fails with [`Error::InvalidRoute`] if the weight isn't between 1 and 256.
+fn next_hop(interface_index: u32, rtr: Option<RouteAddress>, weight: u16) -> Result<RouteNextHop> {
+    let mut hop = RouteNextHop::default();
+    hop.interface_index = interface_index;
+    // The kernel stores the weight minus one.
+    hop.hops = match weight {
+        1..=256 => (weight - 1) as u8,
+        _ => {
+            return Err(Error::InvalidRoute(format!(
+                "nexthop weight {} is out of range (1-256)",
+                weight
+            )))
+        }
+    };
+
+    if let Some(rtr) = rtr {
+        hop.attributes.push(RouteAttribute::Gateway(rtr));
+    }
+
+    Ok(hop)
+}
+
+/// Sets the table of a route message. Table IDs above 255 don't fit
+/// the 8-bit header field, so they're only stored in the `RTA_TABLE` attribute
+/// and the header field is set to `RT_TABLE_UNSPEC` like `ip route` does.
+fn set_table(message: &mut RouteMessage, table: u32) {
+    match u8::try_from(table) {
+        Ok(table) => message.header.table = table,
+        Err(_) => {
+            message.header.table = RouteHeader::RT_TABLE_UNSPEC;
+            message.attributes.push(RouteAttribute::Table(table));
+        }
+    }
+}
+
+/// Prepares a message built by [`Connection::route_message4`]
+/// or [`Connection::route_message6`] for deleting the route.
+/// Like `ip route del` this ignores the scope and protocol of the route.
+fn del_message(mut message: RouteMessage) -> RouteMessage {
+    message.header.scope = RouteScope::NoWhere;
+    message.header.protocol = RouteProtocol::Unspec;
+    message
+}
+
+/// Returns the gateway of a route, if any.
+fn route_gateway(route: &RouteMessage) -> Option<IpAddr> {
+    route.attributes.iter().find_map(|attr| match attr {
+        RouteAttribute::Gateway(RouteAddress::Inet(rtr)) => Some(IpAddr::V4(*rtr)),
+        RouteAttribute::Gateway(RouteAddress::Inet6(rtr)) => Some(IpAddr::V6(*rtr)),
+        _ => None,
+    })
+}
+
+/// Returns the preferred source address of a route, if any.
+fn route_prefsrc(route: &RouteMessage) -> Option<IpAddr> {
+    route.attributes.iter().find_map(|attr| match attr {
+        RouteAttribute::PrefSource(RouteAddress::Inet(prefsrc)) => Some(IpAddr::V4(*prefsrc)),
+        RouteAttribute::PrefSource(RouteAddress::Inet6(prefsrc)) => Some(IpAddr::V6(*prefsrc)),
+        _ => None,
+    })
+}
+
+/// Returns the metric (priority) of a route, if any.
+fn route_metric(route: &RouteMessage) -> Option<u32> {
+    route.attributes.iter().find_map(|attr| {
+        if let RouteAttribute::Priority(metric) = *attr {
+            Some(metric)
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the metrics of a route. Metrics this crate doesn't support are ignored.
+fn route_metrics(route: &RouteMessage) -> RouteMetrics {
+    let mut metrics = RouteMetrics::default();
+    for attr in &route.attributes {
+        if let RouteAttribute::Metrics(nlas) = attr {
+            for nla in nlas {
+                match *nla {
+                    RouteMetric::Mtu(mtu) => metrics.mtu = Some(mtu),
+                    RouteMetric::Advmss(advmss) => metrics.advmss = Some(advmss),
+                    RouteMetric::Hoplimit(hoplimit) => metrics.hoplimit = Some(hoplimit),
+                    RouteMetric::InitCwnd(initcwnd) => metrics.initcwnd = Some(initcwnd),
+                    RouteMetric::InitRwnd(initrwnd) => metrics.initrwnd = Some(initrwnd),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    metrics
+}
+
+/// Converts the metrics that are set to their `RTA_METRICS` sub-attributes.
+fn metric_attributes(metrics: &RouteMetrics) -> Vec<RouteMetric> {
+    [
+        metrics.mtu.map(RouteMetric::Mtu),
+        metrics.advmss.map(RouteMetric::Advmss),
+        metrics.hoplimit.map(RouteMetric::Hoplimit),
+        metrics.initcwnd.map(RouteMetric::InitCwnd),
+        metrics.initrwnd.map(RouteMetric::InitRwnd),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Returns the remaining lifetime of a route, if it expires.
+fn route_expires(route: &RouteMessage) -> Option<Duration> {
+    let expires = route.attributes.iter().find_map(|attr| {
+        if let RouteAttribute::CacheInfo(cache_info) = attr {
+            Some(cache_info.expires as i32)
+        } else {
+            None
+        }
+    })?;
+
+    // The kernel reports the lifetime in clock ticks. Routes that don't expire
+    // have a lifetime of 0, expired ones that haven't been removed yet
+    // a negative lifetime.
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as u64;
+    match expires {
+        0 => None,
+        expires => Some(Duration::from_millis(
+            u64::try_from(expires).unwrap_or(0) * 1000 / ticks,
+        )),
+    }
+}
+
+/// Returns the table a route belongs to, preferring the `RTA_TABLE` attribute
+/// over the 8-bit header field.
+fn route_table(route: &RouteMessage) -> u32 {
+    route
+        .attributes
+        .iter()
+        .find_map(|attr| {
+            if let RouteAttribute::Table(table) = *attr {
+                Some(table)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(route.header.table.into())
+}
+
+/// Returns the destination of a route, which is the unspecified address
+/// of the route's family if the route doesn't have a `RTA_DST` attribute.
+fn route_dst(route: &RouteMessage) -> IpAddr {
+    route
+        .attributes
+        .iter()
+        .find_map(|attr| match attr {
+            RouteAttribute::Destination(RouteAddress::Inet(dst)) => Some(IpAddr::V4(*dst)),
+            RouteAttribute::Destination(RouteAddress::Inet6(dst)) => Some(IpAddr::V6(*dst)),
+            _ => None,
+        })
+        .unwrap_or(match route.header.address_family {
+            AddressFamily::Inet6 => Ipv6Addr::UNSPECIFIED.into(),
+            _ => Ipv4Addr::UNSPECIFIED.into(),
+        })
+}
+
+/// Reports whether an address lies within a network.
+/// Addresses of a different family are never within the network.
+fn is_within(addr: IpAddr, net: IpAddr, prefix_len: u8) -> bool {
+    addr.is_ipv4() == net.is_ipv4() && network(addr, prefix_len) == network(net, prefix_len)
+}
+
+/// Clears all bits of an address beyond the prefix length.
+fn network(addr: IpAddr, prefix_len: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(addr) => {
+            let mask = u32::MAX
+                .checked_shl(32 - u32::from(prefix_len))
+                .unwrap_or(0);
+            Ipv4Addr::from(u32::from(addr) & mask).into()
+        }
+        IpAddr::V6(addr) => {
+            let mask = u128::MAX
+                .checked_shl(128 - u32::from(prefix_len))
+                .unwrap_or(0);
+            Ipv6Addr::from(u128::from(addr) & mask).into()
+        }
+    }
+}
+
+/// Checks that a prefix length fits the address family of the destination
+/// and that the destination doesn't have any bits set beyond the prefix.
+fn check_prefix(dst: IpAddr, prefix_len: u8) -> Result<()> {
+    let max = if dst.is_ipv4() { 32 } else { 128 };
+    if prefix_len > max {
+        return Err(Error::InvalidPrefixLength(prefix_len));
+    }
+
+    if network(dst, prefix_len) != dst {
+        return Err(Error::InvalidRoute(format!(
+            "{}/{} has bits set beyond the prefix",
+            dst, prefix_len
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that a unicast route has a gateway, an outgoing interface
+/// or nexthops. Routes of other types don't forward traffic.
+fn check_next_hop(kind: RouteType, gateway_or_link: bool, multipath: bool) -> Result<()> {
+    if kind == RouteType::Unicast && !gateway_or_link && !multipath {
+        return Err(Error::InvalidRoute(
+            "unicast route has neither a gateway nor an outgoing interface".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use netlink_packet_route::route::{RouteCacheInfo, RouteCacheInfoBuffer};
+    use netlink_packet_utils::Parseable;
+
+    /// Returns the nexthops of the `RTA_MULTIPATH` attribute of a route.
+    fn multipath(message: &RouteMessage) -> &[RouteNextHop] {
+        message
+            .attributes
+            .iter()
+            .find_map(|attr| {
+                if let RouteAttribute::MultiPath(hops) = attr {
+                    Some(hops.as_slice())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn set_table_small() {
+        let mut message = RouteMessage::default();
+        set_table(&mut message, 100);
+
+        assert_eq!(message.header.table, 100);
+        assert!(message.attributes.is_empty());
+    }
+
+    #[test]
+    fn set_table_large() {
+        let mut message = RouteMessage::default();
+        set_table(&mut message, 1000);
+
+        assert_eq!(message.header.table, RouteHeader::RT_TABLE_UNSPEC);
+        assert_eq!(message.attributes, vec![RouteAttribute::Table(1000)]);
+        assert_eq!(route_table(&message), 1000);
+    }
+
+    #[test]
+    fn set_table_boundary() {
+        let mut message = RouteMessage::default();
+        set_table(&mut message, 255);
+        assert_eq!(message.header.table, 255);
+        assert!(message.attributes.is_empty());
+
+        let mut message = RouteMessage::default();
+        set_table(&mut message, 256);
+        assert_eq!(message.header.table, RouteHeader::RT_TABLE_UNSPEC);
+        assert_eq!(message.attributes, vec![RouteAttribute::Table(256)]);
+    }
+
+    #[test]
+    fn next_hop_weight() {
+        assert_eq!(next_hop(1, None, 1).unwrap().hops, 0);
+        assert_eq!(next_hop(1, None, 256).unwrap().hops, 255);
+        assert!(matches!(next_hop(1, None, 0), Err(Error::InvalidRoute(_))));
+        assert!(matches!(
+            next_hop(1, None, 257),
+            Err(Error::InvalidRoute(_))
+        ));
+    }
+
+    #[test]
+    fn next_hop_gateway() {
+        let rtr = RouteAddress::Inet(Ipv4Addr::new(192, 0, 2, 1));
+
+        let hop = next_hop(7, Some(rtr.clone()), 3).unwrap();
+        assert_eq!(hop.interface_index, 7);
+        assert_eq!(hop.attributes, vec![RouteAttribute::Gateway(rtr)]);
+
+        assert!(next_hop(7, None, 3).unwrap().attributes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn multipath4() {
+        let conn = Connection::new().await.unwrap();
+        let lo = conn.resolve_index("lo").await.unwrap();
+
+        let route = Route4 {
+            dst: Ipv4Addr::new(10, 0, 0, 0),
+            prefix_len: 8,
+            nexthops: vec![
+                NextHop4 {
+                    rtr: Some(Ipv4Addr::new(192, 0, 2, 1)),
+                    link: "lo".to_string(),
+                    weight: 1,
+                },
+                NextHop4 {
+                    rtr: None,
+                    link: "lo".to_string(),
+                    weight: 256,
+                },
+            ],
+            ..Default::default()
+        };
+        let message = conn.route_message4(&route).await.unwrap();
+
+        let hops = multipath(&message);
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].interface_index, lo);
+        assert_eq!(hops[0].hops, 0);
+        assert_eq!(
+            hops[0].attributes,
+            vec![RouteAttribute::Gateway(RouteAddress::Inet(Ipv4Addr::new(
+                192, 0, 2, 1
+            )))]
+        );
+        assert_eq!(hops[1].interface_index, lo);
+        assert_eq!(hops[1].hops, 255);
+        assert!(hops[1].attributes.is_empty());
+        assert_eq!(route_oif(&message), None);
+        assert_eq!(route_gateway(&message), None);
+
+        let names = HashMap::from([(lo, "lo".to_string())]);
+        assert_eq!(route4(&message, &names), Some(route));
+    }
+
+    #[tokio::test]
+    async fn multipath6() {
+        let conn = Connection::new().await.unwrap();
+        let lo = conn.resolve_index("lo").await.unwrap();
+
+        let rtr = Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+        let route = Route6 {
+            dst: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0),
+            prefix_len: 32,
+            nexthops: vec![
+                NextHop6 {
+                    rtr: Some(rtr),
+                    link: "lo".to_string(),
+                    weight: 2,
+                },
+                NextHop6 {
+                    rtr: None,
+                    link: "lo".to_string(),
+                    weight: 1,
+                },
+            ],
+            ..Default::default()
+        };
+        let message = conn.route_message6(&route).await.unwrap();
+
+        let hops = multipath(&message);
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].hops, 1);
+        assert_eq!(
+            hops[0].attributes,
+            vec![RouteAttribute::Gateway(RouteAddress::Inet6(rtr))]
+        );
+        assert_eq!(hops[1].hops, 0);
+        assert!(hops[1].attributes.is_empty());
+
+        let names = HashMap::from([(lo, "lo".to_string())]);
+        assert_eq!(route6(&message, &names), Some(route));
+    }
+
+    #[tokio::test]
+    async fn multipath_weight_out_of_range() {
+        let conn = Connection::new().await.unwrap();
+
+        let route = Route4 {
+            nexthops: vec![NextHop4 {
+                rtr: None,
+                link: "lo".to_string(),
+                weight: 0,
+            }],
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            conn.route_message4(&route).await,
+            Err(Error::InvalidRoute(_))
+        ));
+    }
+
+    #[test]
+    fn multipath_omits_unknown_links() {
+        let mut message = RouteMessage::default();
+        message.attributes.push(RouteAttribute::MultiPath(vec![
+            next_hop(1, None, 1).unwrap(),
+            next_hop(2, None, 1).unwrap(),
+        ]));
+
+        let names = HashMap::from([(2, "eth0".to_string())]);
+        let nexthops = route_multipath(&message, &names, |rtr, link, weight| (rtr, link, weight));
+        assert_eq!(nexthops, vec![(None, "eth0".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn prefsrc4() {
+        let conn = Connection::new().await.unwrap();
+        let lo = conn.resolve_index("lo").await.unwrap();
+
+        let prefsrc = Ipv4Addr::new(192, 0, 2, 10);
+        let route = Route4 {
+            dst: Ipv4Addr::new(198, 51, 100, 0),
+            prefix_len: 24,
+            link: Some("lo".to_string()),
+            prefsrc: Some(prefsrc),
+            ..Default::default()
+        };
+        let message = conn.route_message4(&route).await.unwrap();
+
+        assert!(message
+            .attributes
+            .contains(&RouteAttribute::PrefSource(RouteAddress::Inet(prefsrc))));
+
+        let names = HashMap::from([(lo, "lo".to_string())]);
+        assert_eq!(route4(&message, &names), Some(route));
+    }
+
+    #[tokio::test]
+    async fn prefsrc6() {
+        let conn = Connection::new().await.unwrap();
+        let lo = conn.resolve_index("lo").await.unwrap();
+
+        let prefsrc = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 10);
+        let route = Route6 {
+            dst: Ipv6Addr::new(0x2001, 0xdb8, 1, 0, 0, 0, 0, 0),
+            prefix_len: 48,
+            link: Some("lo".to_string()),
+            prefsrc: Some(prefsrc),
+            ..Default::default()
+        };
+        let message = conn.route_message6(&route).await.unwrap();
+
+        assert!(message
+            .attributes
+            .contains(&RouteAttribute::PrefSource(RouteAddress::Inet6(prefsrc))));
+
+        let names = HashMap::from([(lo, "lo".to_string())]);
+        assert_eq!(route6(&message, &names), Some(route));
+    }
+
+    #[tokio::test]
+    async fn no_prefsrc() {
+        let conn = Connection::new().await.unwrap();
+
+        let route = Route4 {
+            link: Some("lo".to_string()),
+            ..Default::default()
+        };
+        let message = conn.route_message4(&route).await.unwrap();
+
+        assert_eq!(route_prefsrc(&message), None);
+    }
+
+    #[tokio::test]
+    async fn kind() {
+        let conn = Connection::new().await.unwrap();
+
+        for kind in [
+            RouteType::Blackhole,
+            RouteType::Unreachable,
+            RouteType::Prohibit,
+        ] {
+            // The gateway and interface are ignored.
+            let route = Route4 {
+                dst: Ipv4Addr::new(203, 0, 113, 0),
+                prefix_len: 24,
+                rtr: Some(Ipv4Addr::new(192, 0, 2, 1)),
+                link: Some("lo".to_string()),
+                kind,
+                ..Default::default()
+            };
+            let message = conn.route_message4(&route).await.unwrap();
+
+            assert_eq!(message.header.kind, kind.into());
+            assert_eq!(route_gateway(&message), None);
+            assert_eq!(route_oif(&message), None);
+            assert!(route_links(&message).is_empty());
+
+            assert_eq!(
+                route4(&message, &HashMap::new()),
+                Some(Route4 {
+                    rtr: None,
+                    link: None,
+                    ..route
+                })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn kind_unicast() {
+        let conn = Connection::new().await.unwrap();
+
+        let route = Route6 {
+            link: Some("lo".to_string()),
+            ..Default::default()
+        };
+        let message = conn.route_message6(&route).await.unwrap();
+
+        assert_eq!(message.header.kind, RtnlRouteType::Unicast);
+        assert!(route_oif(&message).is_some());
+    }
+
+    #[test]
+    fn kind_unsupported() {
+        let mut message = RouteMessage::default();
+        message.header.address_family = AddressFamily::Inet;
+
+        for kind in [
+            RtnlRouteType::Local,
+            RtnlRouteType::Broadcast,
+            RtnlRouteType::Multicast,
+        ] {
+            message.header.kind = kind;
+
+            assert_eq!(RouteType::from_kernel(kind), None);
+            assert_eq!(route4(&message, &HashMap::new()), None);
+        }
+    }
+
+    #[test]
+    fn within() {
+        let net = IpAddr::V4(Ipv4Addr::new(10, 1, 0, 0));
+
+        assert!(is_within(Ipv4Addr::new(10, 1, 2, 3).into(), net, 16));
+        assert!(!is_within(Ipv4Addr::new(10, 2, 0, 0).into(), net, 16));
+        assert!(is_within(Ipv4Addr::new(192, 0, 2, 1).into(), net, 0));
+        assert!(!is_within(Ipv4Addr::new(10, 1, 0, 1).into(), net, 32));
+        assert!(!is_within(Ipv6Addr::UNSPECIFIED.into(), net, 0));
+
+        let net = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0));
+        assert!(is_within(
+            Ipv6Addr::new(0x2001, 0xdb8, 0, 1, 0, 0, 0, 1).into(),
+            net,
+            48
+        ));
+        assert!(!is_within(
+            Ipv6Addr::new(0x2001, 0xdb9, 0, 0, 0, 0, 0, 0).into(),
+            net,
+            32
+        ));
+        assert!(is_within(Ipv6Addr::LOCALHOST.into(), net, 0));
+        assert!(!is_within(Ipv4Addr::UNSPECIFIED.into(), net, 0));
+    }
+
+    #[test]
+    fn prefix() {
+        assert!(check_prefix(Ipv4Addr::new(10, 0, 0, 0).into(), 8).is_ok());
+        assert!(check_prefix(Ipv4Addr::new(10, 0, 0, 1).into(), 32).is_ok());
+        assert!(check_prefix(Ipv6Addr::UNSPECIFIED.into(), 0).is_ok());
+        assert!(check_prefix(Ipv6Addr::LOCALHOST.into(), 128).is_ok());
+
+        assert!(matches!(
+            check_prefix(Ipv4Addr::new(10, 0, 0, 0).into(), 33),
+            Err(Error::InvalidPrefixLength(33))
+        ));
+        assert!(matches!(
+            check_prefix(Ipv6Addr::UNSPECIFIED.into(), 129),
+            Err(Error::InvalidPrefixLength(129))
+        ));
+        assert!(matches!(
+            check_prefix(Ipv4Addr::new(10, 0, 0, 1).into(), 8),
+            Err(Error::InvalidRoute(_))
+        ));
+        assert!(matches!(
+            check_prefix(Ipv6Addr::LOCALHOST.into(), 64),
+            Err(Error::InvalidRoute(_))
+        ));
+    }
+
+    #[test]
+    fn next_hop_required() {
+        assert!(Route4::default().check_next_hop().is_err());
+        assert!(Route6::default().check_next_hop().is_err());
+
+        let route = Route4 {
+            link: Some("lo".to_string()),
+            ..Default::default()
+        };
+        assert!(route.check_next_hop().is_ok());
+
+        let route = Route6 {
+            rtr: Some(Ipv6Addr::LOCALHOST),
+            ..Default::default()
+        };
+        assert!(route.check_next_hop().is_ok());
+
+        let route = Route4 {
+            kind: RouteType::Blackhole,
+            ..Default::default()
+        };
+        assert!(route.check_next_hop().is_ok());
+    }
+
+    #[tokio::test]
+    async fn validate() {
+        let conn = Connection::new().await.unwrap();
+
+        let route = Route4 {
+            dst: Ipv4Addr::new(10, 0, 0, 0),
+            prefix_len: 8,
+            link: Some("lo".to_string()),
+            ..Default::default()
+        };
+        assert!(conn.route_validate4(&route).await.is_ok());
+
+        let route = Route4 {
+            link: Some("nonexistent0".to_string()),
+            ..route
+        };
+        assert!(matches!(
+            conn.route_validate4(&route).await,
+            Err(Error::LinkNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn via() {
+        let conn = Connection::new().await.unwrap();
+        let lo = conn.resolve_index("lo").await.unwrap();
+
+        let via = Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+        let route = Route4 {
+            dst: Ipv4Addr::new(10, 0, 0, 0),
+            prefix_len: 8,
+            via: Some(via),
+            link: Some("lo".to_string()),
+            ..Default::default()
+        };
+        let message = conn.route_message4(&route).await.unwrap();
+
+        assert!(message
+            .attributes
+            .contains(&RouteAttribute::Via(RouteVia::Inet6(via))));
+        assert_eq!(route_gateway(&message), None);
+
+        let names = HashMap::from([(lo, "lo".to_string())]);
+        assert_eq!(route4(&message, &names), Some(route.clone()));
+
+        let route = Route4 {
+            rtr: Some(Ipv4Addr::new(192, 0, 2, 1)),
+            ..route
+        };
+        assert!(matches!(
+            conn.route_message4(&route).await,
+            Err(Error::InvalidRoute(_))
+        ));
+    }
+
+    #[test]
+    fn metrics() {
+        let metrics = RouteMetrics {
+            mtu: Some(1400),
+            advmss: Some(1360),
+            ..Default::default()
+        };
+
+        let mut message = RouteMessage::default();
+        message
+            .attributes
+            .push(RouteAttribute::Metrics(metric_attributes(&metrics)));
+
+        assert_eq!(
+            message.attributes,
+            vec![RouteAttribute::Metrics(vec![
+                RouteMetric::Mtu(1400),
+                RouteMetric::Advmss(1360)
+            ])]
+        );
+        assert_eq!(route_metrics(&message), metrics);
+
+        assert!(metric_attributes(&RouteMetrics::default()).is_empty());
+        assert_eq!(
+            route_metrics(&RouteMessage::default()),
+            RouteMetrics::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn metrics_unset() {
+        let conn = Connection::new().await.unwrap();
+
+        let route = Route4 {
+            link: Some("lo".to_string()),
+            ..Default::default()
+        };
+        let message = conn.route_message4(&route).await.unwrap();
+
+        assert!(!message
+            .attributes
+            .iter()
+            .any(|attr| matches!(attr, RouteAttribute::Metrics(_))));
+    }
+
+    /// Builds a route message with cache information
+    /// whose lifetime is `expires` clock ticks.
+    fn cache_info_message(expires: i32) -> RouteMessage {
+        let mut buf = [0; 32];
+        buf[8..12].copy_from_slice(&expires.to_ne_bytes());
+
+        let mut message = RouteMessage::default();
+        message.attributes.push(RouteAttribute::CacheInfo(
+            RouteCacheInfo::parse(&RouteCacheInfoBuffer::new(&buf)).unwrap(),
+        ));
+        message
+    }
+
+    #[test]
+    fn expires() {
+        // Linux reports clock ticks in units of USER_HZ, which is 100.
+        assert_eq!(
+            route_expires(&cache_info_message(3050)),
+            Some(Duration::from_millis(30500))
+        );
+        assert_eq!(route_expires(&cache_info_message(0)), None);
+        assert_eq!(
+            route_expires(&cache_info_message(-100)),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(route_expires(&RouteMessage::default()), None);
+    }
+
+    #[tokio::test]
+    async fn expires_rounded() {
+        let conn = Connection::new().await.unwrap();
+
+        let route = Route6 {
+            link: Some("lo".to_string()),
+            expires: Some(Duration::from_millis(300900)),
+            ..Default::default()
+        };
+        let message = conn.route_message6(&route).await.unwrap();
+
+        assert!(message.attributes.contains(&RouteAttribute::Expires(300)));
+    }
 }
@@ -1,18 +1,65 @@
-//! Owned 6in4 and 4in6 tunnels with automatic deletion on drop.
-
-use crate::{Error, Result};
+//! Owned 6in4, 4in6 and GRE tunnels with automatic deletion on drop.
+//!
+//! Tunnels can optionally be created in a different network namespace
+//! using the `new_in_netns` constructors. These temporarily move the calling
+//! thread into the namespace using `setns(2)`, which only affects that thread.
+//! The namespace is switched and restored on the calling thread
+//! before anything yields, so they're safe to call from async tasks.
+//! Only code interrupting the thread in the meantime (e.g. signal handlers)
+//! observes the target namespace.
+//!
+//! Creating a tunnel with a name that is already taken fails with [`Error::TunnelExists`].
+//! Creating or deleting a tunnel without `CAP_NET_ADMIN`
+//! fails with [`Error::PermissionDenied`].
+
+use crate::{netns, Error, Result};
 
 use std::ffi::{c_char, c_int, CString};
 use std::io;
+use std::mem;
 use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
 
 const SIOCADDTUNNEL: c_int = 0x89F0 + 1;
 const SIOCDELTUNNEL: c_int = 0x89F0 + 2;
+const SIOCADD6RD: c_int = 0x89F0 + 9;
+
+const GRE_KEY: u16 = 0x2000;
+const IP_DF: u16 = 0x4000;
+const IP6_TNL_F_USE_ORIG_TCLASS: u32 = 0x2;
+
+/// Outer header options of a tunnel.
+///
+/// The defaults match the tunnels created by [`Sit::new`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TunnelOptions {
+    /// The TTL (IPv4) or hop limit (IPv6) of encapsulated packets.
+    /// 0 inherits it from the inner packet. Defaults to 64.
+    pub ttl: u8,
+    /// The TOS (IPv4) or traffic class (IPv6) of encapsulated packets,
+    /// including the DSCP. 1 inherits it from the inner packet. Defaults to 0.
+    pub tos: u8,
+    /// Whether to set the don't fragment bit of encapsulated packets.
+    /// Can only be disabled if the TTL is inherited. Ignored by IPv6 tunnels.
+    /// Defaults to `true`.
+    pub pmtudisc: bool,
+}
+
+impl Default for TunnelOptions {
+    fn default() -> Self {
+        Self {
+            ttl: 64,
+            tos: 0,
+            pmtudisc: true,
+        }
+    }
+}
 
 /// A handle to a 6in4 tunnel. The interface is automatically deleted on drop.
 #[derive(Debug)]
 pub struct Sit {
     name: String,
+    netns: Option<PathBuf>,
 }
 
 impl Drop for Sit {
@@ -29,10 +76,73 @@ impl Sit {
     /// * `name` - The name of the tunnel to be created.
     /// * `master` - The name of the parent interface for actual traffic.
     /// * `laddr` - The address of the local tunnel endpoint,
-    ///             e.g. the WAN IPv4 address of a router.
+    ///   e.g. the WAN IPv4 address of a router.
     /// * `raddr` - The address of the remote tunnel endpoint, e.g. a tunnel server.
     pub fn new(name: String, master: String, laddr: Ipv4Addr, raddr: Ipv4Addr) -> Result<Self> {
-        let tnlname = CString::new(&*name)?;
+        Self::create(&name, master, laddr, raddr, TunnelOptions::default())?;
+        Ok(Self { name, netns: None })
+    }
+
+    /// Creates a new 6in4 tunnel on a parent device
+    /// with custom outer header options, e.g. to preserve the DSCP.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tunnel to be created.
+    /// * `master` - The name of the parent interface for actual traffic.
+    /// * `laddr` - The address of the local tunnel endpoint.
+    /// * `raddr` - The address of the remote tunnel endpoint.
+    /// * `opts` - The TTL, TOS and path MTU discovery settings of the outer header.
+    pub fn with_options(
+        name: String,
+        master: String,
+        laddr: Ipv4Addr,
+        raddr: Ipv4Addr,
+        opts: TunnelOptions,
+    ) -> Result<Self> {
+        Self::create(&name, master, laddr, raddr, opts)?;
+        Ok(Self { name, netns: None })
+    }
+
+    /// Creates a new 6in4 tunnel on a parent device inside a network namespace.
+    /// The parent device must be located in the same namespace.
+    /// The tunnel is deleted from that namespace on drop.
+    ///
+    /// See the [module documentation](self) for the threading caveats.
+    ///
+    /// # Arguments
+    ///
+    /// * `netns` - The path of the network namespace, e.g. `/var/run/netns/foo`.
+    /// * `name` - The name of the tunnel to be created.
+    /// * `master` - The name of the parent interface for actual traffic.
+    /// * `laddr` - The address of the local tunnel endpoint.
+    /// * `raddr` - The address of the remote tunnel endpoint.
+    pub fn new_in_netns(
+        netns: PathBuf,
+        name: String,
+        master: String,
+        laddr: Ipv4Addr,
+        raddr: Ipv4Addr,
+    ) -> Result<Self> {
+        netns::with_netns(&netns, || {
+            Self::create(&name, master, laddr, raddr, TunnelOptions::default())
+        })?;
+        Ok(Self {
+            name,
+            netns: Some(netns),
+        })
+    }
+
+    fn create(
+        name: &str,
+        master: String,
+        laddr: Ipv4Addr,
+        raddr: Ipv4Addr,
+        opts: TunnelOptions,
+    ) -> Result<()> {
+        opts.validate()?;
+
+        let tnlname = CString::new(name)?;
         let ifmaster = CString::new(&*master)?;
         let sit0 = CString::new("sit0")?;
 
@@ -64,12 +174,12 @@ impl Sit {
             o_key: 0,
             iph: IpHdr4 {
                 vihl,
-                tos: 0,
+                tos: opts.tos,
                 tot_len: 0,
                 id: 0,
-                frag_off: 0,
+                frag_off: if opts.pmtudisc { IP_DF.to_be() } else { 0 },
                 check: 0,
-                ttl: 64,
+                ttl: opts.ttl,
                 protocol: libc::IPPROTO_IPV6 as u8,
                 saddr: u32::from(laddr).to_be(),
                 daddr: u32::from(raddr).to_be(),
@@ -90,9 +200,11 @@ impl Sit {
             return Err(io::Error::last_os_error().into());
         }
 
-        if unsafe { libc::ioctl(fd, SIOCADDTUNNEL, &ifr) } < 0 {
-            return Err(io::Error::last_os_error().into());
-        }
+        let result = if unsafe { libc::ioctl(fd, SIOCADDTUNNEL as _, &ifr) } < 0 {
+            Err(tunnel_error(name))
+        } else {
+            Ok(())
+        };
 
         // Errors are safe to ignore because they don't affect tunnel creation
         // but do leave the program in an inconsistent state.
@@ -100,11 +212,64 @@ impl Sit {
             libc::close(fd);
         }
 
-        Ok(Self { name })
+        result
+    }
+
+    /// Configures the 6rd (IPv6 rapid deployment) parameters of the tunnel.
+    ///
+    /// This uses the `SIOCADD6RD` ioctl which takes a `struct ip_tunnel_6rd`:
+    ///
+    /// ```c
+    /// struct ip_tunnel_6rd {
+    ///     struct in6_addr prefix;
+    ///     __be32 relay_prefix;
+    ///     __u16 prefixlen;
+    ///     __u16 relay_prefixlen;
+    /// };
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The 6rd prefix assigned by the ISP.
+    /// * `prefix_len` - The length of the 6rd prefix.
+    /// * `relay_prefix` - The common IPv4 prefix of the border relays.
+    /// * `relay_prefix_len` - The length of the relay prefix, 0 if the full IPv4 address is embedded.
+    pub fn set_6rd(
+        &self,
+        prefix: Ipv6Addr,
+        prefix_len: u8,
+        relay_prefix: Ipv4Addr,
+        relay_prefix_len: u8,
+    ) -> Result<()> {
+        let p = IpTunnel6rd {
+            prefix: prefix.octets(),
+            relay_prefix: u32::from(relay_prefix).to_be(),
+            prefixlen: prefix_len.into(),
+            relay_prefixlen: relay_prefix_len.into(),
+        };
+
+        match self.netns {
+            Some(ref netns) => netns::with_netns(netns, || set_6rd(&self.name, &p)),
+            None => set_6rd(&self.name, &p),
+        }
+    }
+
+    /// Deletes the tunnel. Unlike the automatic deletion on drop
+    /// this reports errors, e.g. if the process lacks the necessary privileges.
+    /// On failure the handle is dropped, which attempts the deletion once more.
+    pub fn delete(mut self) -> Result<()> {
+        self.do_delete()?;
+
+        // Free the fields without running Drop which would delete the tunnel again.
+        drop(mem::take(&mut self.name));
+        drop(self.netns.take());
+        mem::forget(self);
+
+        Ok(())
     }
 
     fn do_delete(&self) -> Result<()> {
-        delete_tunnel(&self.name)
+        delete_tunnel_in(self.netns.as_deref(), &self.name)
     }
 }
 
@@ -112,6 +277,7 @@ impl Sit {
 #[derive(Debug)]
 pub struct IpIp6 {
     name: String,
+    netns: Option<PathBuf>,
 }
 
 impl Drop for IpIp6 {
@@ -130,7 +296,69 @@ impl IpIp6 {
     /// * `laddr` - The address of the local tunnel endpoint, e.g. the IPv6 GUA of a DS-Lite B4.
     /// * `raddr` - The address of the remote tunnel endpoint, e.g. a DS-Lite AFTR.
     pub fn new(name: String, master: String, laddr: Ipv6Addr, raddr: Ipv6Addr) -> Result<Self> {
-        let tnlname = CString::new(&*name)?;
+        Self::create(&name, master, laddr, raddr, None)?;
+        Ok(Self { name, netns: None })
+    }
+
+    /// Creates a new 4in6 tunnel on a parent device
+    /// with custom outer header options, e.g. to preserve the DSCP.
+    ///
+    /// [`IpIp6::new`] inherits the hop limit from the inner packet,
+    /// which corresponds to a `ttl` of 0 rather than the default options.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tunnel to be created.
+    /// * `master` - The name of the parent interface for actual traffic.
+    /// * `laddr` - The address of the local tunnel endpoint.
+    /// * `raddr` - The address of the remote tunnel endpoint.
+    /// * `opts` - The hop limit and traffic class of the outer header.
+    pub fn with_options(
+        name: String,
+        master: String,
+        laddr: Ipv6Addr,
+        raddr: Ipv6Addr,
+        opts: TunnelOptions,
+    ) -> Result<Self> {
+        Self::create(&name, master, laddr, raddr, Some(opts))?;
+        Ok(Self { name, netns: None })
+    }
+
+    /// Creates a new 4in6 tunnel on a parent device inside a network namespace.
+    /// The parent device must be located in the same namespace.
+    /// The tunnel is deleted from that namespace on drop.
+    ///
+    /// See the [module documentation](self) for the threading caveats.
+    ///
+    /// # Arguments
+    ///
+    /// * `netns` - The path of the network namespace, e.g. `/var/run/netns/foo`.
+    /// * `name` - The name of the tunnel to be created.
+    /// * `master` - The name of the parent interface for actual traffic.
+    /// * `laddr` - The address of the local tunnel endpoint.
+    /// * `raddr` - The address of the remote tunnel endpoint.
+    pub fn new_in_netns(
+        netns: PathBuf,
+        name: String,
+        master: String,
+        laddr: Ipv6Addr,
+        raddr: Ipv6Addr,
+    ) -> Result<Self> {
+        netns::with_netns(&netns, || Self::create(&name, master, laddr, raddr, None))?;
+        Ok(Self {
+            name,
+            netns: Some(netns),
+        })
+    }
+
+    fn create(
+        name: &str,
+        master: String,
+        laddr: Ipv6Addr,
+        raddr: Ipv6Addr,
+        opts: Option<TunnelOptions>,
+    ) -> Result<()> {
+        let tnlname = CString::new(name)?;
         let ifmaster = CString::new(&*master)?;
         let ip6tnl0 = CString::new("ip6tnl0")?;
 
@@ -148,17 +376,23 @@ impl IpIp6 {
             *o = i;
         }
 
+        let (hop_limit, flowinfo, flags) = match opts {
+            // The traffic class is stored in bits 20-27 of the flow information.
+            Some(opts) if opts.tos == 1 => (opts.ttl, 0, IP6_TNL_F_USE_ORIG_TCLASS),
+            Some(opts) => (opts.ttl, (u32::from(opts.tos) << 20).to_be(), 0),
+            None => (0, 0, 0),
+        };
+
         let p = IpTunnelParm6 {
             name: tnlname_arr,
             link: unsafe { libc::if_nametoindex(ifmaster.as_ptr()) },
-            i_flags: 0,
-            o_flags: 0,
-            i_key: 0,
-            o_key: 0,
-            iph: IpHdr6 {
-                saddr: u128::from(laddr).to_be(),
-                daddr: u128::from(raddr).to_be(),
-            },
+            proto: 0,
+            encap_limit: 0,
+            hop_limit,
+            flowinfo,
+            flags,
+            laddr: u128::from(laddr).to_be(),
+            raddr: u128::from(raddr).to_be(),
         };
 
         if p.link == 0 {
@@ -175,21 +409,276 @@ impl IpIp6 {
             return Err(io::Error::last_os_error().into());
         }
 
-        if unsafe { libc::ioctl(fd, SIOCADDTUNNEL, &ifr) } < 0 {
+        let result = if unsafe { libc::ioctl(fd, SIOCADDTUNNEL as _, &ifr) } < 0 {
+            Err(tunnel_error(name))
+        } else {
+            Ok(())
+        };
+
+        // Errors are safe to ignore because they don't affect tunnel creation
+        // but do leave the program in an inconsistent state.
+        unsafe {
+            libc::close(fd);
+        }
+
+        result
+    }
+
+    /// Deletes the tunnel. Unlike the automatic deletion on drop
+    /// this reports errors, e.g. if the process lacks the necessary privileges.
+    /// On failure the handle is dropped, which attempts the deletion once more.
+    pub fn delete(mut self) -> Result<()> {
+        self.do_delete()?;
+
+        // Free the fields without running Drop which would delete the tunnel again.
+        drop(mem::take(&mut self.name));
+        drop(self.netns.take());
+        mem::forget(self);
+
+        Ok(())
+    }
+
+    fn do_delete(&self) -> Result<()> {
+        delete_tunnel_in(self.netns.as_deref(), &self.name)
+    }
+}
+
+/// A handle to a GRE tunnel. The interface is automatically deleted on drop.
+#[derive(Debug)]
+pub struct Gre {
+    name: String,
+    netns: Option<PathBuf>,
+}
+
+impl Drop for Gre {
+    fn drop(&mut self) {
+        let _ = self.do_delete();
+    }
+}
+
+impl Gre {
+    /// Creates a new GRE tunnel on a parent device.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tunnel to be created.
+    /// * `master` - The name of the parent interface for actual traffic.
+    /// * `laddr` - The address of the local tunnel endpoint.
+    /// * `raddr` - The address of the remote tunnel endpoint, e.g. the other site of a site-to-site link.
+    /// * `ikey` - The key incoming packets must carry, if any.
+    /// * `okey` - The key to add to outgoing packets, if any.
+    pub fn new(
+        name: String,
+        master: String,
+        laddr: Ipv4Addr,
+        raddr: Ipv4Addr,
+        ikey: Option<u32>,
+        okey: Option<u32>,
+    ) -> Result<Self> {
+        Self::create(&name, master, laddr, raddr, ikey, okey)?;
+        Ok(Self { name, netns: None })
+    }
+
+    /// Creates a new GRE tunnel on a parent device inside a network namespace.
+    /// The parent device must be located in the same namespace.
+    /// The tunnel is deleted from that namespace on drop.
+    ///
+    /// See the [module documentation](self) for the threading caveats.
+    ///
+    /// # Arguments
+    ///
+    /// * `netns` - The path of the network namespace, e.g. `/var/run/netns/foo`.
+    /// * `name` - The name of the tunnel to be created.
+    /// * `master` - The name of the parent interface for actual traffic.
+    /// * `laddr` - The address of the local tunnel endpoint.
+    /// * `raddr` - The address of the remote tunnel endpoint.
+    /// * `ikey` - The key incoming packets must carry, if any.
+    /// * `okey` - The key to add to outgoing packets, if any.
+    pub fn new_in_netns(
+        netns: PathBuf,
+        name: String,
+        master: String,
+        laddr: Ipv4Addr,
+        raddr: Ipv4Addr,
+        ikey: Option<u32>,
+        okey: Option<u32>,
+    ) -> Result<Self> {
+        netns::with_netns(&netns, || {
+            Self::create(&name, master, laddr, raddr, ikey, okey)
+        })?;
+        Ok(Self {
+            name,
+            netns: Some(netns),
+        })
+    }
+
+    fn create(
+        name: &str,
+        master: String,
+        laddr: Ipv4Addr,
+        raddr: Ipv4Addr,
+        ikey: Option<u32>,
+        okey: Option<u32>,
+    ) -> Result<()> {
+        let tnlname = CString::new(name)?;
+        let ifmaster = CString::new(&*master)?;
+        let gre0 = CString::new("gre0")?;
+
+        #[allow(clippy::unnecessary_cast)]
+        let tnlname_raw = unsafe { &*(tnlname.as_bytes() as *const _ as *const [c_char]) };
+        let mut tnlname_arr = [0; libc::IFNAMSIZ];
+        for (&i, o) in tnlname_raw.iter().zip(tnlname_arr.iter_mut()) {
+            *o = i;
+        }
+
+        #[allow(clippy::unnecessary_cast)]
+        let gre0_raw = unsafe { &*(gre0.as_bytes() as *const _ as *const [c_char]) };
+        let mut gre0_arr = [0; libc::IFNAMSIZ];
+        for (&i, o) in gre0_raw.iter().zip(gre0_arr.iter_mut()) {
+            *o = i;
+        }
+
+        let mut vihl = VerIhl::default();
+
+        vihl.set_version(4);
+        vihl.set_ihl(5);
+
+        // The flags and keys are in network byte order.
+        let p = IpTunnelParm4 {
+            name: tnlname_arr,
+            link: unsafe { libc::if_nametoindex(ifmaster.as_ptr()) },
+            i_flags: if ikey.is_some() { GRE_KEY.to_be() } else { 0 },
+            o_flags: if okey.is_some() { GRE_KEY.to_be() } else { 0 },
+            i_key: ikey.unwrap_or_default().to_be(),
+            o_key: okey.unwrap_or_default().to_be(),
+            iph: IpHdr4 {
+                vihl,
+                tos: 0,
+                tot_len: 0,
+                id: 0,
+                frag_off: 0,
+                check: 0,
+                ttl: 64,
+                protocol: libc::IPPROTO_GRE as u8,
+                saddr: u32::from(laddr).to_be(),
+                daddr: u32::from(raddr).to_be(),
+            },
+        };
+
+        if p.link == 0 {
+            return Err(Error::LinkNotFound(master));
+        }
+
+        let ifr = IfReq4 {
+            name: gre0_arr,
+            ifru_data: &p,
+        };
+
+        let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, libc::IPPROTO_IP) };
+        if fd < 0 {
             return Err(io::Error::last_os_error().into());
         }
 
+        let result = if unsafe { libc::ioctl(fd, SIOCADDTUNNEL as _, &ifr) } < 0 {
+            Err(tunnel_error(name))
+        } else {
+            Ok(())
+        };
+
         // Errors are safe to ignore because they don't affect tunnel creation
         // but do leave the program in an inconsistent state.
         unsafe {
             libc::close(fd);
         }
 
-        Ok(Self { name })
+        result
+    }
+
+    /// Deletes the tunnel. Unlike the automatic deletion on drop
+    /// this reports errors, e.g. if the process lacks the necessary privileges.
+    /// On failure the handle is dropped, which attempts the deletion once more.
+    pub fn delete(mut self) -> Result<()> {
+        self.do_delete()?;
+
+        // Free the fields without running Drop which would delete the tunnel again.
+        drop(mem::take(&mut self.name));
+        drop(self.netns.take());
+        mem::forget(self);
+
+        Ok(())
     }
 
     fn do_delete(&self) -> Result<()> {
-        delete_tunnel(&self.name)
+        delete_tunnel_in(self.netns.as_deref(), &self.name)
+    }
+}
+
+/// Deletes a 6in4, 4in6 or GRE tunnel by name without a handle to it,
+/// e.g. to clean up a tunnel leaked by a previous run of the program.
+///
+/// Fails with [`Error::LinkNotFound`] if no interface with this name exists.
+pub fn delete(name: &str) -> Result<()> {
+    delete_tunnel(name).map_err(|e| match e {
+        Error::Io(e) if e.raw_os_error() == Some(libc::ENODEV) => {
+            Error::LinkNotFound(name.to_string())
+        }
+        e => e,
+    })
+}
+
+impl TunnelOptions {
+    fn validate(&self) -> Result<()> {
+        // The kernel silently sets the don't fragment bit if the TTL isn't inherited.
+        if !self.pmtudisc && self.ttl != 0 {
+            return Err(Error::InvalidTunnelOpts(format!(
+                "ttl {} requires path MTU discovery",
+                self.ttl
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+fn set_6rd(name: &str, p: &IpTunnel6rd) -> Result<()> {
+    let tnlname = CString::new(name)?;
+
+    #[allow(clippy::unnecessary_cast)]
+    let tnlname_raw = unsafe { &*(tnlname.as_bytes() as *const _ as *const [c_char]) };
+    let mut tnlname_arr = [0; libc::IFNAMSIZ];
+    for (&i, o) in tnlname_raw.iter().zip(tnlname_arr.iter_mut()) {
+        *o = i;
+    }
+
+    let ifr = IfReq6rd {
+        name: tnlname_arr,
+        ifru_data: p,
+    };
+
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, libc::IPPROTO_IP) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let result = if unsafe { libc::ioctl(fd, SIOCADD6RD as _, &ifr) } < 0 {
+        Err(io::Error::last_os_error().into())
+    } else {
+        Ok(())
+    };
+
+    // Errors are safe to ignore because they don't affect the configuration
+    // but do leave the program in an inconsistent state.
+    unsafe {
+        libc::close(fd);
+    }
+
+    result
+}
+
+fn delete_tunnel_in(netns: Option<&Path>, name: &str) -> Result<()> {
+    match netns {
+        Some(netns) => netns::with_netns(netns, || delete_tunnel(name)),
+        None => delete_tunnel(name),
     }
 }
 
@@ -234,9 +723,11 @@ fn delete_tunnel(name: &str) -> Result<()> {
         return Err(io::Error::last_os_error().into());
     }
 
-    if unsafe { libc::ioctl(fd, SIOCDELTUNNEL, &ifr) } < 0 {
-        return Err(io::Error::last_os_error().into());
-    }
+    let result = if unsafe { libc::ioctl(fd, SIOCDELTUNNEL as _, &ifr) } < 0 {
+        Err(tunnel_error(name))
+    } else {
+        Ok(())
+    };
 
     // Errors are safe to ignore because they don't affect tunnel deletion
     // but do leave the program in an inconsistent state.
@@ -244,7 +735,19 @@ fn delete_tunnel(name: &str) -> Result<()> {
         libc::close(fd);
     }
 
-    Ok(())
+    result
+}
+
+/// Converts the error of the last tunnel ioctl,
+/// mapping name collisions and missing privileges to dedicated variants.
+fn tunnel_error(name: &str) -> Error {
+    let e = io::Error::last_os_error();
+
+    match e.raw_os_error() {
+        Some(libc::EEXIST) => Error::TunnelExists(name.to_string()),
+        Some(libc::EPERM) => Error::PermissionDenied,
+        _ => e.into(),
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -294,23 +797,18 @@ struct IfReq4 {
     ifru_data: *const IpTunnelParm4,
 }
 
-#[derive(Debug)]
-#[repr(C)]
-struct IpHdr6 {
-    saddr: u128,
-    daddr: u128,
-}
-
 #[derive(Debug)]
 #[repr(C)]
 struct IpTunnelParm6 {
     name: [c_char; libc::IFNAMSIZ],
     link: u32,
-    i_flags: u16,
-    o_flags: u16,
-    i_key: u32,
-    o_key: u32,
-    iph: IpHdr6,
+    proto: u8,
+    encap_limit: u8,
+    hop_limit: u8,
+    flowinfo: u32,
+    flags: u32,
+    laddr: u128,
+    raddr: u128,
 }
 
 #[derive(Debug)]
@@ -319,3 +817,19 @@ struct IfReq6 {
     name: [c_char; libc::IFNAMSIZ],
     ifru_data: *const IpTunnelParm6,
 }
+
+#[derive(Debug)]
+#[repr(C)]
+struct IpTunnel6rd {
+    prefix: [u8; 16],
+    relay_prefix: u32,
+    prefixlen: u16,
+    relay_prefixlen: u16,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+struct IfReq6rd {
+    name: [c_char; libc::IFNAMSIZ],
+    ifru_data: *const IpTunnel6rd,
+}